@@ -0,0 +1,141 @@
+use crate::registry;
+
+pub const MANIFEST: &str = "answers.txt";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Expected {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub answer: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Verdict {
+    Correct,
+    Mismatch { actual: String },
+    Failed(String),
+}
+
+/// Parses a manifest of `day part input expected` lines, skipping blank lines and `#` comments.
+pub fn parse(manifest: &str) -> Result<Vec<Expected>, String> {
+    manifest
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| {
+            let error = |message: &str| format!("line {}: {message} (at {line:?})", i + 1);
+            let mut fields = line.split_whitespace();
+            let mut field = |name: &str| {
+                fields
+                    .next()
+                    .ok_or_else(|| error(&format!("missing {name}")))
+            };
+            let day = field("day")?.parse().map_err(|_| error("invalid day"))?;
+            let part = field("part")?.parse().map_err(|_| error("invalid part"))?;
+            let input = field("input")?.to_string();
+            let answer = field("expected answer")?.to_string();
+            if fields.next().is_some() {
+                return Err(error("unexpected trailing field"));
+            }
+            Ok(Expected {
+                day,
+                part,
+                input,
+                answer,
+            })
+        })
+        .collect()
+}
+
+pub fn check(expected: &Expected, input: &str) -> Verdict {
+    let Some(solution) = registry::find(expected.day, expected.part) else {
+        return Verdict::Failed("no solution registered".to_string());
+    };
+    match solution.run(input) {
+        Ok(answer) if answer.value == expected.answer => Verdict::Correct,
+        Ok(answer) => Verdict::Mismatch {
+            actual: answer.value,
+        },
+        Err(e) => Verdict::Failed(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_example() {
+        let manifest = indoc! {"
+            # day part input expected
+            1 1 input/2023/day1.txt 53334
+
+            15 2 examples/day15.txt 145
+        "};
+
+        assert_eq!(
+            parse(manifest),
+            Ok(vec![
+                Expected {
+                    day: 1,
+                    part: 1,
+                    input: "input/2023/day1.txt".into(),
+                    answer: "53334".into(),
+                },
+                Expected {
+                    day: 15,
+                    part: 2,
+                    input: "examples/day15.txt".into(),
+                    answer: "145".into(),
+                },
+            ])
+        );
+        assert_eq!(
+            parse("1 x input.txt 5"),
+            Err("line 1: invalid part (at \"1 x input.txt 5\")".into())
+        );
+        assert_eq!(
+            parse("\n1 1 input.txt"),
+            Err("line 2: missing expected answer (at \"1 1 input.txt\")".into())
+        );
+    }
+
+    #[test]
+    fn check_example() {
+        let input = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+        let mut expected = Expected {
+            day: 15,
+            part: 2,
+            input: String::new(),
+            answer: "145".into(),
+        };
+
+        assert_eq!(check(&expected, input), Verdict::Correct);
+
+        expected.part = 1;
+        assert_eq!(
+            check(&expected, input),
+            Verdict::Mismatch {
+                actual: "1320".into()
+            }
+        );
+
+        expected.day = 26;
+        assert_eq!(
+            check(&expected, input),
+            Verdict::Failed("no solution registered".into())
+        );
+    }
+
+    #[test]
+    fn manifest_covers_all_solutions() {
+        let manifest = parse(include_str!("../answers.txt")).unwrap();
+        for solution in registry::SOLUTIONS {
+            assert!(manifest
+                .iter()
+                .any(|expected| expected.day == solution.day && expected.part == solution.part));
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Stats {
+    pub mean: Duration,
+    pub median: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let nanos = samples
+            .iter()
+            .map(|sample| sample.as_nanos() as f64)
+            .sorted_by(f64::total_cmp)
+            .collect_vec();
+        let mean = nanos.iter().sum::<f64>() / nanos.len() as f64;
+        let median = if nanos.len() % 2 == 0 {
+            (nanos[nanos.len() / 2 - 1] + nanos[nanos.len() / 2]) / 2.
+        } else {
+            nanos[nanos.len() / 2]
+        };
+        let variance = nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / nanos.len() as f64;
+
+        Some(Self {
+            mean: Duration::from_nanos(mean.round() as u64),
+            median: Duration::from_nanos(median.round() as u64),
+            stddev: Duration::from_nanos(variance.sqrt().round() as u64),
+        })
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "mean {:.2?}, median {:.2?}, stddev {:.2?}",
+            self.mean, self.median, self.stddev
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Measurement {
+    pub day: u8,
+    pub part: u8,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Relative change of the median from `baseline` to `current`, in percent,
+/// or `None` when the baseline median is zero.
+pub fn change(baseline: &Stats, current: &Stats) -> Option<f64> {
+    let baseline = baseline.median.as_nanos() as f64;
+    let current = current.median.as_nanos() as f64;
+    (baseline > 0.).then(|| (current - baseline) / baseline * 100.)
+}
+
+pub fn save_baseline(measurements: &[Measurement]) -> String {
+    let mut result =
+        String::from("# day part parse_mean parse_median parse_stddev solve_mean solve_median solve_stddev (ns)\n");
+    for m in measurements {
+        result += &format!(
+            "{} {} {} {} {} {} {} {}\n",
+            m.day,
+            m.part,
+            m.parse.mean.as_nanos(),
+            m.parse.median.as_nanos(),
+            m.parse.stddev.as_nanos(),
+            m.solve.mean.as_nanos(),
+            m.solve.median.as_nanos(),
+            m.solve.stddev.as_nanos(),
+        );
+    }
+    result
+}
+
+pub fn load_baseline(baseline: &str) -> Result<HashMap<(u8, u8), Measurement>, String> {
+    baseline
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            let error = || format!("line {}: expected 8 numbers (at {line:?})", i + 1);
+            let values: Vec<u64> = line
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .map_err(|_| error())?;
+            let [day, part, parse_mean, parse_median, parse_stddev, solve_mean, solve_median, solve_stddev] =
+                values[..]
+            else {
+                return Err(error());
+            };
+            let (day, part) = (
+                u8::try_from(day).map_err(|_| error())?,
+                u8::try_from(part).map_err(|_| error())?,
+            );
+            Ok((
+                (day, part),
+                Measurement {
+                    day,
+                    part,
+                    parse: Stats {
+                        mean: Duration::from_nanos(parse_mean),
+                        median: Duration::from_nanos(parse_median),
+                        stddev: Duration::from_nanos(parse_stddev),
+                    },
+                    solve: Stats {
+                        mean: Duration::from_nanos(solve_mean),
+                        median: Duration::from_nanos(solve_median),
+                        stddev: Duration::from_nanos(solve_stddev),
+                    },
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_example() {
+        let samples = [2, 4, 4, 4, 5, 5, 7, 9].map(Duration::from_micros);
+
+        assert_eq!(
+            Stats::new(&samples),
+            Some(Stats {
+                mean: Duration::from_micros(5),
+                median: Duration::from_nanos(4500),
+                stddev: Duration::from_micros(2),
+            })
+        );
+        assert_eq!(
+            Stats::new(&samples[..3]).map(|stats| stats.median),
+            Some(Duration::from_micros(4))
+        );
+        assert_eq!(Stats::new(&[]), None);
+    }
+
+    #[test]
+    fn baseline_roundtrip() {
+        let stats = |micros| Stats {
+            mean: Duration::from_micros(micros),
+            median: Duration::from_micros(micros - 1),
+            stddev: Duration::from_nanos(micros * 10),
+        };
+        let measurements = vec![
+            Measurement {
+                day: 5,
+                part: 2,
+                parse: stats(12),
+                solve: stats(101),
+            },
+            Measurement {
+                day: 14,
+                part: 1,
+                parse: stats(3),
+                solve: stats(7),
+            },
+        ];
+
+        let baseline = load_baseline(&save_baseline(&measurements)).unwrap();
+        assert_eq!(baseline.len(), 2);
+        assert_eq!(baseline[&(5, 2)], measurements[0]);
+        assert_eq!(baseline[&(14, 1)], measurements[1]);
+        assert_eq!(
+            load_baseline("1 2 3"),
+            Err("line 1: expected 8 numbers (at \"1 2 3\")".into())
+        );
+    }
+
+    #[test]
+    fn change_example() {
+        let stats = |micros| Stats {
+            mean: Duration::ZERO,
+            median: Duration::from_micros(micros),
+            stddev: Duration::ZERO,
+        };
+
+        assert_eq!(change(&stats(200), &stats(150)), Some(-25.));
+        assert_eq!(change(&stats(200), &stats(300)), Some(50.));
+        assert_eq!(change(&stats(0), &stats(300)), None);
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A sequence that repeats with `period` after the first `prefix` states.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The smallest step that has the same state as step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.period
+        }
+    }
+
+    pub fn state_at<T>(&self, start: T, mut step: impl FnMut(&T) -> T, n: usize) -> T {
+        (0..self.reduce(n)).fold(start, |state, _| step(&state))
+    }
+}
+
+/// The states visited until the first repetition, indexed by step.
+#[derive(Debug, Clone)]
+pub struct History<T> {
+    pub cycle: Cycle,
+    pub states: Vec<T>,
+}
+
+impl<T> History<T> {
+    pub fn state_at(&self, n: usize) -> &T {
+        &self.states[self.cycle.reduce(n)]
+    }
+}
+
+pub fn find_cycle<T: Hash + Eq + Clone>(start: T, mut step: impl FnMut(&T) -> T) -> History<T> {
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = start;
+    while !seen.contains_key(&state) {
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+
+    let prefix = seen[&state];
+    History {
+        cycle: Cycle {
+            prefix,
+            period: states.len() - prefix,
+        },
+        states,
+    }
+}
+
+pub fn state_at<T: Hash + Eq + Clone>(start: T, step: impl FnMut(&T) -> T, n: usize) -> T {
+    find_cycle(start, step).state_at(n).clone()
+}
+
+/// Floyd's tortoise and hare, which only ever keeps two states around.
+pub fn floyd<T: Eq + Clone>(start: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut prefix = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+
+    Cycle { prefix, period }
+}
+
+/// Brent's algorithm, which needs fewer steps than Floyd's for long prefixes.
+pub fn brent<T: Eq + Clone>(start: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    let mut prefix = 0;
+    tortoise = start.clone();
+    hare = (0..period).fold(start, |state, _| step(&state));
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    Cycle { prefix, period }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(x: &u32) -> u32 {
+        (x * x + 1) % 255
+    }
+
+    #[test]
+    fn find_cycle_example() {
+        let history = find_cycle(3, step);
+        let expected = Cycle {
+            prefix: 2,
+            period: 6,
+        };
+
+        assert_eq!(history.cycle, expected);
+        assert_eq!(floyd(3, step), expected);
+        assert_eq!(brent(3, step), expected);
+
+        for n in [0, 1, 2, 7, 8, 100, 1000] {
+            let brute_force = (0..n).fold(3, |x, _| step(&x));
+            assert_eq!(*history.state_at(n), brute_force);
+            assert_eq!(expected.state_at(3, step, n), brute_force);
+            assert_eq!(state_at(3, step, n), brute_force);
+        }
+    }
+
+    #[test]
+    fn find_cycle_without_prefix() {
+        let step = |x: &u8| (x + 1) % 5;
+        let expected = Cycle {
+            prefix: 0,
+            period: 5,
+        };
+
+        assert_eq!(find_cycle(0, step).cycle, expected);
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(brent(0, step), expected);
+        assert_eq!(state_at(0, step, 1_000_000_000), 0);
+        assert_eq!(find_cycle(7, |_: &u8| 7).cycle.period, 1);
+    }
+}
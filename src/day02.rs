@@ -1,50 +1,33 @@
+use crate::parse::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
 
 #[aoc_generator(day2)]
-fn parse(input: &str) -> Vec<(u32, u32, u32, u32)> {
+fn parse(input: &str) -> Result<Vec<(u32, u32, u32, u32)>, ParseError> {
+    let source = Source::new(2, input);
     input
         .lines()
         .map(|line| {
-            let (left, right) = line.split_once(':').unwrap();
+            let (left, right) = source.split_once(line, ":")?;
 
-            let left = left.replace("Game ", "").parse().unwrap();
+            let left = source.parse(source.strip_prefix(left, "Game ")?)?;
 
             let (red, green, blue) = right
                 .split(';')
-                .map(|split| {
-                    split
-                        .split(',')
-                        .map(str::trim)
-                        .map(|split| {
-                            let (num, color) = split.split_once(' ').unwrap();
-
-                            (color, num.parse::<u32>().unwrap())
-                        })
-                        .fold((0, 0, 0), |mut acc, (color, num)| {
-                            match color {
-                                "red" if acc.0 < num => acc.0 = num,
-                                "green" if acc.1 < num => acc.1 = num,
-                                "blue" if acc.2 < num => acc.2 = num,
-                                _ => {}
-                            }
-                            acc
-                        })
-                })
-                .reduce(|mut acc, (red, green, blue)| {
-                    if acc.0 < red {
-                        acc.0 = red;
-                    }
-                    if acc.1 < green {
-                        acc.1 = green;
-                    }
-                    if acc.2 < blue {
-                        acc.2 = blue;
+                .flat_map(|split| split.split(',').map(str::trim))
+                .try_fold((0, 0, 0), |mut acc, split| {
+                    let (num, color) = source.split_once(split, " ")?;
+                    let num = source.parse::<u32>(num)?;
+
+                    match color {
+                        "red" => acc.0 = acc.0.max(num),
+                        "green" => acc.1 = acc.1.max(num),
+                        "blue" => acc.2 = acc.2.max(num),
+                        _ => return Err(source.error(color, "unknown color")),
                     }
-                    acc
-                })
-                .unwrap();
+                    Ok(acc)
+                })?;
 
-            (left, red, green, blue)
+            Ok((left, red, green, blue))
         })
         .collect()
 }
@@ -83,13 +66,32 @@ mod tests {
 
         assert_eq!(
             parse(input),
-            vec![
+            Ok(vec![
                 (1, 4, 2, 6),
                 (2, 1, 3, 4),
                 (3, 20, 13, 6),
                 (4, 14, 3, 15),
                 (5, 6, 3, 2)
-            ]
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        let input = indoc! {"
+            Game 1: 3 blue, 4 red
+            Game 2: 1 blue, 2 purple
+        "};
+
+        assert_eq!(
+            parse(input),
+            Err(ParseError {
+                day: 2,
+                line: 2,
+                column: 19,
+                text: "purple".into(),
+                message: "unknown color".into(),
+            })
         );
     }
 
@@ -103,7 +105,7 @@ mod tests {
             Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
         "};
 
-        assert_eq!(part1(&parse(input)), 8);
+        assert_eq!(part1(&parse(input).unwrap()), 8);
     }
 
     #[test]
@@ -116,6 +118,6 @@ mod tests {
             Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
         "};
 
-        assert_eq!(part2(&parse(input)), 2286);
+        assert_eq!(part2(&parse(input).unwrap()), 2286);
     }
 }
@@ -1,31 +1,26 @@
+use crate::grid::{parse_grid, Grid, GridExt, Pos};
+use crate::parse::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
 
 #[aoc_generator(day3)]
-fn parse(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|line| line.chars().collect()).collect()
+fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+    parse_grid(&Source::new(3, input), input)
 }
 
 #[aoc(day3, part1)]
-pub fn part1(input: &Vec<Vec<char>>) -> u32 {
+pub fn part1(input: &Grid<char>) -> u32 {
     let mut sum = 0;
-    for y in 0..input.len() {
+    for y in 0..input.rows() {
         let mut x = 0;
-        while x < input[y].len() {
+        while x < input.cols() {
             let mut current_number = String::new();
             let mut is_included = false;
-            while x < input[y].len() && input[y][x].is_ascii_digit() {
-                current_number.push(input[y][x]);
+            while x < input.cols() && input[(y, x)].is_ascii_digit() {
+                current_number.push(input[(y, x)]);
 
-                let checks = get_checks(y, x);
-
-                for (y, x) in checks {
-                    if y.is_negative() || x.is_negative() {
-                        continue;
-                    }
-                    if let Some(Some(c)) = input.get(y as usize).map(|v| v.get(x as usize)) {
-                        is_included |= !c.is_ascii_digit() && *c != '.';
-                    }
-                }
+                is_included |= input
+                    .neighbours8(Pos::new(y, x))
+                    .any(|pos| !input[pos].is_ascii_digit() && input[pos] != '.');
 
                 x += 1;
             }
@@ -41,82 +36,58 @@ pub fn part1(input: &Vec<Vec<char>>) -> u32 {
 }
 
 #[aoc(day3, part2)]
-fn part2(input: &Vec<Vec<char>>) -> u32 {
+fn part2(input: &Grid<char>) -> u32 {
     let mut sum = 0;
 
-    for y in 0..input.len() {
-        for x in 0..input[y].len() {
-            if input[y][x] == '*' {
-                let checks = get_checks(y, x);
+    for pos in input.positions() {
+        if input[pos] == '*' {
+            let mut vec = input
+                .neighbours8(pos)
+                .filter(|&pos| input[pos].is_ascii_digit())
+                .collect::<Vec<_>>();
+
+            let mut i = 0;
+            while i < vec.len() {
+                let Pos { row, col } = vec[i];
+                if (col > 0 && vec.contains(&Pos::new(row, col - 1)))
+                    || vec.contains(&Pos::new(row, col + 1))
+                {
+                    vec.remove(i);
+                } else {
+                    i += 1;
+                }
+            }
 
-                let mut vec = vec![];
+            if vec.len() != 2 {
+                continue;
+            }
 
-                for (y, x) in checks {
-                    if y.is_negative() || x.is_negative() {
-                        continue;
-                    }
-                    if let Some(Some(c)) = input.get(y as usize).map(|v| v.get(x as usize)) {
-                        if c.is_ascii_digit() {
-                            vec.push((y, x));
-                        }
-                    }
-                }
+            sum += vec
+                .iter()
+                .map(|&Pos { row, mut col }| {
+                    let mut num = String::new();
 
-                let mut i = 0;
-                while i < vec.len() {
-                    let (y, x) = vec[i];
-                    if vec.contains(&(y, x - 1)) || vec.contains(&(y, x + 1)) {
-                        vec.remove(i);
-                    } else {
-                        i += 1;
+                    while col > 0 && input[(row, col - 1)].is_ascii_digit() {
+                        col -= 1;
+                    }
+                    while col < input.cols() && input[(row, col)].is_ascii_digit() {
+                        num.push(input[(row, col)]);
+                        col += 1;
                     }
-                }
-
-                if vec.len() != 2 {
-                    continue;
-                }
 
-                sum += vec
-                    .iter()
-                    .map(|(y, mut x)| {
-                        let mut num = String::new();
-
-                        while x > 0 && input[*y as usize][(x - 1) as usize].is_ascii_digit() {
-                            x -= 1;
-                        }
-                        while x < input[*y as usize].len() as i32
-                            && input[*y as usize][x as usize].is_ascii_digit()
-                        {
-                            num.push(input[*y as usize][x as usize]);
-                            x += 1;
-                        }
-
-                        num.parse::<u32>().unwrap()
-                    })
-                    .product::<u32>();
-            }
+                    num.parse::<u32>().unwrap()
+                })
+                .product::<u32>();
         }
     }
 
     sum
 }
 
-fn get_checks(y: usize, x: usize) -> [(i32, i32); 8] {
-    [
-        (y as i32 - 1, x as i32 - 1),
-        (y as i32 - 1, x as i32),
-        (y as i32 - 1, x as i32 + 1),
-        (y as i32, x as i32 - 1),
-        (y as i32, x as i32 + 1),
-        (y as i32 + 1, x as i32 - 1),
-        (y as i32 + 1, x as i32),
-        (y as i32 + 1, x as i32 + 1),
-    ]
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use grid::grid;
     use indoc::indoc;
 
     #[test]
@@ -134,20 +105,20 @@ mod tests {
             .664.598..
         "};
 
-        let result = vec![
-            vec!['4', '6', '7', '.', '.', '1', '1', '4', '.', '.'],
-            vec!['.', '.', '.', '*', '.', '.', '.', '.', '.', '.'],
-            vec!['.', '.', '3', '5', '.', '.', '6', '3', '3', '.'],
-            vec!['.', '.', '.', '.', '.', '.', '#', '.', '.', '.'],
-            vec!['6', '1', '7', '*', '.', '.', '.', '.', '.', '.'],
-            vec!['.', '.', '.', '.', '.', '+', '.', '5', '8', '.'],
-            vec!['.', '.', '5', '9', '2', '.', '.', '.', '.', '.'],
-            vec!['.', '.', '.', '.', '.', '.', '7', '5', '5', '.'],
-            vec!['.', '.', '.', '$', '.', '*', '.', '.', '.', '.'],
-            vec!['.', '6', '6', '4', '.', '5', '9', '8', '.', '.'],
+        let result = grid![
+            ['4', '6', '7', '.', '.', '1', '1', '4', '.', '.']
+            ['.', '.', '.', '*', '.', '.', '.', '.', '.', '.']
+            ['.', '.', '3', '5', '.', '.', '6', '3', '3', '.']
+            ['.', '.', '.', '.', '.', '.', '#', '.', '.', '.']
+            ['6', '1', '7', '*', '.', '.', '.', '.', '.', '.']
+            ['.', '.', '.', '.', '.', '+', '.', '5', '8', '.']
+            ['.', '.', '5', '9', '2', '.', '.', '.', '.', '.']
+            ['.', '.', '.', '.', '.', '.', '7', '5', '5', '.']
+            ['.', '.', '.', '$', '.', '*', '.', '.', '.', '.']
+            ['.', '6', '6', '4', '.', '5', '9', '8', '.', '.']
         ];
 
-        assert_eq!(parse(input), result);
+        assert_eq!(parse(input), Ok(result));
     }
 
     #[test]
@@ -165,7 +136,7 @@ mod tests {
             .664.598..
         "};
 
-        assert_eq!(part1(&parse(input)), 4361);
+        assert_eq!(part1(&parse(input).unwrap()), 4361);
     }
 
     #[test]
@@ -183,6 +154,6 @@ mod tests {
             .664.598..
         "};
 
-        assert_eq!(part2(&parse(input)), 467835);
+        assert_eq!(part2(&parse(input).unwrap()), 467835);
     }
 }
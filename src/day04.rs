@@ -1,4 +1,7 @@
+use crate::number::{self, Int, Overflow};
+use crate::parse::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
+use num_traits::{One, Zero};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct Game {
@@ -7,65 +10,58 @@ struct Game {
     containing: Vec<u32>,
 }
 
+impl Game {
+    fn matches(&self) -> usize {
+        self.containing
+            .iter()
+            .filter(|num| self.winning.contains(num))
+            .count()
+    }
+}
+
 #[aoc_generator(day4)]
-fn parse(input: &str) -> Vec<Game> {
+fn parse(input: &str) -> Result<Vec<Game>, ParseError> {
+    let source = Source::new(4, input);
+    let numbers = |numbers: &str| {
+        numbers
+            .split_whitespace()
+            .map(|s| source.parse(s))
+            .collect::<Result<_, _>>()
+    };
     input
         .lines()
         .map(|line| {
-            let (left, right) = line.split_once(':').unwrap();
-            let id = left.replace("Card ", "").trim().parse().unwrap();
-            let (winning, containing) = right.split_once('|').unwrap();
-            Game {
+            let (left, right) = source.split_once(line, ":")?;
+            let id = source.parse(source.strip_prefix(left, "Card ")?.trim())?;
+            let (winning, containing) = source.split_once(right, "|")?;
+            Ok(Game {
                 id,
-                winning: winning
-                    .trim()
-                    .split(' ')
-                    .filter_map(|s| s.parse().ok())
-                    .collect(),
-                containing: containing
-                    .trim()
-                    .split(' ')
-                    .filter_map(|s| s.parse().ok())
-                    .collect(),
-            }
+                winning: numbers(winning)?,
+                containing: numbers(containing)?,
+            })
         })
         .collect()
 }
 
 #[aoc(day4, part1)]
-fn part1(input: &[Game]) -> u32 {
-    input
-        .iter()
-        .map(|game| {
-            2u32.pow(
-                game.containing
-                    .iter()
-                    .filter(|num| game.winning.contains(num))
-                    .count() as u32,
-            ) / 2
-        })
-        .sum()
+fn part1(input: &[Game]) -> Result<Int, Overflow> {
+    number::sum(input.iter().map(|game| match game.matches() {
+        0 => Ok(Int::zero()),
+        matches => number::pow(Int::from(2u64), matches - 1),
+    }))
 }
 
 #[aoc(day4, part2)]
-fn part2(input: &[Game]) -> u32 {
-    let mut games: Vec<(u32, &Game)> = input.iter().map(|game| (1, game)).collect();
-    let mut i = 0;
-    while i < games.len() {
-        let (copies, game) = games[i];
-        let new_cards = game
-            .containing
-            .iter()
-            .filter(|num| game.winning.contains(num))
-            .count();
-        for j in 1..(new_cards + 1) {
-            if i + j < games.len() {
-                games[i + j].0 += copies;
-            }
+fn part2(input: &[Game]) -> Result<Int, Overflow> {
+    let mut games: Vec<(Int, &Game)> = input.iter().map(|game| (Int::one(), game)).collect();
+    for i in 0..games.len() {
+        let (done, rest) = games.split_at_mut(i + 1);
+        let (copies, game) = &done[i];
+        for (next, _) in rest.iter_mut().take(game.matches()) {
+            *next = number::add(next, copies)?;
         }
-        i += 1;
     }
-    games.iter().map(|(copies, _)| *copies).sum()
+    number::sum(games.into_iter().map(|(copies, _)| Ok(copies)))
 }
 
 #[cfg(test)]
@@ -117,7 +113,7 @@ mod tests {
             },
         ];
 
-        assert_eq!(parse(input), result);
+        assert_eq!(parse(input), Ok(result));
     }
 
     #[test]
@@ -131,7 +127,7 @@ mod tests {
             Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
         "};
 
-        assert_eq!(part1(&parse(input)), 13);
+        assert_eq!(part1(&parse(input).unwrap()), Ok(Int::from(13u64)));
     }
 
     #[test]
@@ -145,6 +141,6 @@ mod tests {
             Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
         "};
 
-        assert_eq!(part2(&parse(input)), 30);
+        assert_eq!(part2(&parse(input).unwrap()), Ok(Int::from(30u64)));
     }
 }
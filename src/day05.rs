@@ -1,125 +1,164 @@
+use crate::interval::{IntervalMap, Segment};
+use crate::parse::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
-use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
 use std::ops::Range;
 
 #[derive(Debug, PartialEq, Eq)]
 struct Almanac {
     seeds: Seeds,
-    seed_to_soil: Map,
-    soil_to_fertilizer: Map,
-    fertilizer_to_water: Map,
-    water_to_light: Map,
-    light_to_temperature: Map,
-    temperature_to_humidity: Map,
-    humidity_to_location: Map,
+    stages: Vec<Stage>,
 }
 
+/// One "X-to-Y map:" section.
 #[derive(Debug, PartialEq, Eq)]
-enum Seeds {
-    Single(Vec<u64>),
-    Ranges(Vec<Range<u64>>),
+struct Stage {
+    from: String,
+    to: String,
+    map: Map,
 }
 
-type Map = HashMap<Range<u64>, Range<u64>>;
-
-trait RangeGet {
-    fn range_get(&self, value: u64) -> u64;
+#[derive(Debug, PartialEq, Eq)]
+enum PathError {
+    UnknownCategory(String),
+    NoPath(String, String),
+    Ambiguous(String, String),
 }
 
-impl RangeGet for Map {
-    fn range_get(&self, value: u64) -> u64 {
-        self.keys()
-            .find(|key| key.contains(&value))
-            .and_then(|key| self.get_key_value(key))
-            .map(|(src, dest)| map(value, src.start, src.end - 1, dest.start, dest.end - 1))
-            .unwrap_or(value)
+impl Display for PathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::UnknownCategory(name) => write!(f, "unknown category {name:?}"),
+            PathError::NoPath(from, to) => write!(f, "no chain of maps from {from:?} to {to:?}"),
+            PathError::Ambiguous(from, to) => {
+                write!(f, "more than one chain of maps from {from:?} to {to:?}")
+            }
+        }
     }
 }
 
-fn map(x: u64, in_min: u64, in_max: u64, out_min: u64, out_max: u64) -> u64 {
-    ((x - in_min) as f64 * (out_max - out_min) as f64 / (in_max - in_min) as f64) as u64 + out_min
-}
-
-fn map_ranges(range: Range<u64>, map: &Map) -> Vec<Range<u64>> {
-    let mut vec = map.keys().collect_vec();
-    vec.sort_by_key(|range| range.start);
+impl Error for PathError {}
 
-    let mut result = Vec::new();
-    let mut start = range.start;
+impl Almanac {
+    fn has_category(&self, name: &str) -> bool {
+        self.stages
+            .iter()
+            .any(|stage| stage.from == name || stage.to == name)
+    }
 
-    for r in vec {
-        if r.start > start {
-            if range.end <= r.start {
-                continue;
-            } else {
-                result.push(start..r.start);
-                start = r.start;
+    /// Finds the only chain of stages leading from `from` to `to`.
+    fn path(&self, from: &str, to: &str) -> Result<Vec<&Stage>, PathError> {
+        for name in [from, to] {
+            if !self.has_category(name) {
+                return Err(PathError::UnknownCategory(name.to_string()));
             }
         }
-        if start > r.end {
-            continue;
+
+        let mut paths = Vec::new();
+        let mut path = Vec::new();
+        self.find_paths(from, to, &mut path, &mut paths);
+        match paths.len() {
+            0 => Err(PathError::NoPath(from.to_string(), to.to_string())),
+            1 => Ok(paths.pop().unwrap()),
+            _ => Err(PathError::Ambiguous(from.to_string(), to.to_string())),
+        }
+    }
+
+    /// Collects up to two simple paths, which is enough to tell whether the path is unique.
+    fn find_paths<'a>(
+        &'a self,
+        from: &str,
+        to: &str,
+        path: &mut Vec<&'a Stage>,
+        paths: &mut Vec<Vec<&'a Stage>>,
+    ) {
+        if from == to {
+            paths.push(path.clone());
+            return;
         }
-        if r.end <= range.end {
-            result.push(start..r.end);
-            start = r.end;
+        for stage in self.stages.iter().filter(|stage| stage.from == from) {
+            let visited = stage.to == from || path.iter().any(|s| s.from == stage.to);
+            if visited || paths.len() > 1 {
+                continue;
+            }
+            path.push(stage);
+            self.find_paths(&stage.to, to, path, paths);
+            path.pop();
         }
     }
 
-    if range.end > start {
-        result.push(start..range.end);
+    /// Composes the stages between two categories into a single map.
+    fn map(&self, from: &str, to: &str) -> Result<Map, PathError> {
+        Ok(self
+            .path(from, to)?
+            .iter()
+            .fold(Map::default(), |composed, stage| composed.then(&stage.map)))
     }
 
-    result
-        .iter()
-        .map(
-            |range| match map.iter().find(|(from, _)| from.contains(&range.start)) {
-                None => range.clone(),
-                Some((from, to)) => {
-                    if from == range {
-                        to.clone()
-                    } else if from.start < range.start && from.end == range.end {
-                        (to.start + (range.start - from.start))..to.end
-                    } else if from.start == range.start && from.end > range.end {
-                        to.start..(to.end - (from.end - range.end))
-                    } else {
-                        (to.start + (range.start - from.start))..(to.end - (from.end - range.end))
-                    }
-                }
-            },
-        )
-        .collect_vec()
+    fn seed_to_location(&self) -> Result<Map, PathError> {
+        self.map("seed", "location")
+    }
 }
 
-fn parse(input: &str, seeds_are_ranges: bool) -> Almanac {
-    let input: Vec<&str> = input.split("\n\n").collect();
-
-    fn to_range_map(input: &str) -> Map {
-        input
-            .lines()
-            .skip(1)
-            .map(|line| {
-                let values = line
-                    .split_whitespace()
-                    .map(str::parse)
-                    .filter_map(Result::ok)
-                    .collect::<Vec<u64>>();
-                (
-                    values[1]..(values[1] + values[2]),
-                    values[0]..(values[0] + values[2]),
-                )
+#[derive(Debug, PartialEq, Eq)]
+enum Seeds {
+    Single(Vec<u64>),
+    Ranges(Vec<Range<u64>>),
+}
+
+type Map = IntervalMap;
+
+fn parse(input: &str, seeds_are_ranges: bool) -> Result<Almanac, ParseError> {
+    let source = Source::new(5, input);
+    let mut sections = input.split("\n\n");
+
+    let to_stage = |section: &str| -> Result<Stage, ParseError> {
+        let mut lines = section.lines();
+        let header = lines.next().unwrap_or_default();
+        let (from, to) = source.split_once(source.strip_suffix(header, " map:")?, "-to-")?;
+        let mut map = Map::default();
+        for line in lines {
+            let (dest, src, length) = line
+                .split_whitespace()
+                .map(|value| source.parse::<u64>(value))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .collect_tuple()
+                .ok_or_else(|| source.error(line, "expected 3 numbers"))?;
+            if src.checked_add(length).is_none() || dest.checked_add(length).is_none() {
+                return Err(source.error(line, "range exceeds u64"));
+            }
+            map.insert(Segment {
+                source: src..(src + length),
+                dest,
             })
-            .collect()
-    }
+            .map_err(|other| {
+                source.error(
+                    line,
+                    format!("overlaps with source range {:?}", other.source),
+                )
+            })?;
+        }
+        Ok(Stage {
+            from: from.to_string(),
+            to: to.to_string(),
+            map,
+        })
+    };
 
-    let seeds = input[0]
-        .replace("seeds: ", "")
+    let seeds_section = sections.next().unwrap_or_default();
+    let seeds = source
+        .strip_prefix(seeds_section, "seeds: ")?
         .split_whitespace()
-        .map(str::parse)
-        .filter_map(Result::ok)
-        .collect::<Vec<u64>>();
+        .map(|seed| source.parse(seed))
+        .collect::<Result<Vec<u64>, _>>()?;
 
     let seeds = if seeds_are_ranges {
+        if seeds.len() % 2 != 0 {
+            return Err(source.error(seeds_section, "expected pairs of seed ranges"));
+        }
         Seeds::Ranges(
             seeds
                 .iter()
@@ -132,63 +171,36 @@ fn parse(input: &str, seeds_are_ranges: bool) -> Almanac {
         Seeds::Single(seeds)
     };
 
-    Almanac {
+    Ok(Almanac {
         seeds,
-        seed_to_soil: to_range_map(input[1]),
-        soil_to_fertilizer: to_range_map(input[2]),
-        fertilizer_to_water: to_range_map(input[3]),
-        water_to_light: to_range_map(input[4]),
-        light_to_temperature: to_range_map(input[5]),
-        temperature_to_humidity: to_range_map(input[6]),
-        humidity_to_location: to_range_map(input[7]),
-    }
+        stages: sections.map(to_stage).collect::<Result<_, _>>()?,
+    })
 }
 
 #[aoc_generator(day5, part1)]
-fn parse_part1(input: &str) -> Almanac {
+fn parse_part1(input: &str) -> Result<Almanac, ParseError> {
     parse(input, false)
 }
 
 #[aoc_generator(day5, part2)]
-fn parse_part2(input: &str) -> Almanac {
+fn parse_part2(input: &str) -> Result<Almanac, ParseError> {
     parse(input, true)
 }
 
 #[aoc(day5, part1)]
-fn part1(almanac: &Almanac) -> u64 {
+fn part1(almanac: &Almanac) -> Result<u64, PathError> {
     if let Seeds::Single(seeds) = &almanac.seeds {
-        seeds
-            .iter()
-            .copied()
-            .map(|seed| almanac.seed_to_soil.range_get(seed))
-            .map(|soil| almanac.soil_to_fertilizer.range_get(soil))
-            .map(|fertilizer| almanac.fertilizer_to_water.range_get(fertilizer))
-            .map(|water| almanac.water_to_light.range_get(water))
-            .map(|light| almanac.light_to_temperature.range_get(light))
-            .map(|temperature| almanac.temperature_to_humidity.range_get(temperature))
-            .map(|humidity| almanac.humidity_to_location.range_get(humidity))
-            .min()
-            .unwrap()
+        let map = almanac.seed_to_location()?;
+        Ok(seeds.iter().map(|&seed| map.get(seed)).min().unwrap())
     } else {
         unreachable!()
     }
 }
 
 #[aoc(day5, part2)]
-fn part2(almanac: &Almanac) -> u64 {
+fn part2(almanac: &Almanac) -> Result<u64, PathError> {
     if let Seeds::Ranges(seeds) = &almanac.seeds {
-        seeds
-            .iter()
-            .flat_map(|range| map_ranges(range.clone(), &almanac.seed_to_soil))
-            .flat_map(|range| map_ranges(range.clone(), &almanac.soil_to_fertilizer))
-            .flat_map(|range| map_ranges(range.clone(), &almanac.fertilizer_to_water))
-            .flat_map(|range| map_ranges(range.clone(), &almanac.water_to_light))
-            .flat_map(|range| map_ranges(range.clone(), &almanac.light_to_temperature))
-            .flat_map(|range| map_ranges(range.clone(), &almanac.temperature_to_humidity))
-            .flat_map(|range| map_ranges(range.clone(), &almanac.humidity_to_location))
-            .map(|range| range.start)
-            .min()
-            .unwrap()
+        Ok(almanac.seed_to_location()?.min_over(seeds).unwrap())
     } else {
         unreachable!()
     }
@@ -235,73 +247,162 @@ mod tests {
         56 93 4
     "};
 
+    fn stage(from: &str, to: &str, map: Map) -> Stage {
+        Stage {
+            from: from.into(),
+            to: to.into(),
+            map,
+        }
+    }
+
+    fn example_stages() -> Vec<Stage> {
+        vec![
+            stage(
+                "seed",
+                "soil",
+                Map::from_iter([(98..100, 50), (50..98, 52)]),
+            ),
+            stage(
+                "soil",
+                "fertilizer",
+                Map::from_iter([(15..52, 0), (52..54, 37), (0..15, 39)]),
+            ),
+            stage(
+                "fertilizer",
+                "water",
+                Map::from_iter([(53..61, 49), (11..53, 0), (0..7, 42), (7..11, 57)]),
+            ),
+            stage(
+                "water",
+                "light",
+                Map::from_iter([(18..25, 88), (25..95, 18)]),
+            ),
+            stage(
+                "light",
+                "temperature",
+                Map::from_iter([(77..100, 45), (45..64, 81), (64..77, 68)]),
+            ),
+            stage(
+                "temperature",
+                "humidity",
+                Map::from_iter([(69..70, 0), (0..69, 1)]),
+            ),
+            stage(
+                "humidity",
+                "location",
+                Map::from_iter([(56..93, 60), (93..97, 56)]),
+            ),
+        ]
+    }
+
     #[test]
     fn parse_part1_example() {
         let result = Almanac {
             seeds: Seeds::Single(vec![79, 14, 55, 13]),
-            seed_to_soil: HashMap::from([(98..100, 50..52), (50..98, 52..100)]),
-            soil_to_fertilizer: HashMap::from([(15..52, 0..37), (52..54, 37..39), (0..15, 39..54)]),
-            fertilizer_to_water: HashMap::from([
-                (53..61, 49..57),
-                (11..53, 0..42),
-                (0..7, 42..49),
-                (7..11, 57..61),
-            ]),
-            water_to_light: HashMap::from([(18..25, 88..95), (25..95, 18..88)]),
-            light_to_temperature: HashMap::from([
-                (77..100, 45..68),
-                (45..64, 81..100),
-                (64..77, 68..81),
-            ]),
-            temperature_to_humidity: HashMap::from([(69..70, 0..1), (0..69, 1..70)]),
-            humidity_to_location: HashMap::from([(56..93, 60..97), (93..97, 56..60)]),
+            stages: example_stages(),
         };
 
-        assert_eq!(parse_part1(INPUT), result);
+        assert_eq!(parse_part1(INPUT), Ok(result));
     }
 
     #[test]
     fn parse_part2_example() {
         let result = Almanac {
             seeds: Seeds::Ranges(vec![79..93, 55..68]),
-            seed_to_soil: HashMap::from([(98..100, 50..52), (50..98, 52..100)]),
-            soil_to_fertilizer: HashMap::from([(15..52, 0..37), (52..54, 37..39), (0..15, 39..54)]),
-            fertilizer_to_water: HashMap::from([
-                (53..61, 49..57),
-                (11..53, 0..42),
-                (0..7, 42..49),
-                (7..11, 57..61),
-            ]),
-            water_to_light: HashMap::from([(18..25, 88..95), (25..95, 18..88)]),
-            light_to_temperature: HashMap::from([
-                (77..100, 45..68),
-                (45..64, 81..100),
-                (64..77, 68..81),
-            ]),
-            temperature_to_humidity: HashMap::from([(69..70, 0..1), (0..69, 1..70)]),
-            humidity_to_location: HashMap::from([(56..93, 60..97), (93..97, 56..60)]),
+            stages: example_stages(),
         };
 
-        assert_eq!(parse_part2(INPUT), result);
+        assert_eq!(parse_part2(INPUT), Ok(result));
     }
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_part1(INPUT)), 35);
+        assert_eq!(part1(&parse_part1(INPUT).unwrap()), Ok(35));
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_part2(INPUT)), 46);
+        assert_eq!(part2(&parse_part2(INPUT).unwrap()), Ok(46));
+    }
+
+    #[test]
+    fn seed_to_location() {
+        let almanac = parse_part1(INPUT).unwrap();
+        let map = almanac.seed_to_location().unwrap();
+
+        assert_eq!([79, 14, 55, 13].map(|seed| map.get(seed)), [82, 43, 86, 35]);
+        assert!(map.preimage(35).contains(&13));
+        assert!(map.preimage(46).contains(&82));
+        for location in 0..100 {
+            for seed in map.preimage(location) {
+                assert_eq!(map.get(seed), location);
+            }
+        }
+        assert_eq!(map.breakpoints().first(), Some(&0));
+        assert_eq!(map.breakpoints().last(), Some(&100));
+    }
+
+    #[test]
+    fn paths() {
+        let almanac = parse_part1(INPUT).unwrap();
+        let soil_to_light = almanac.map("soil", "light").unwrap();
+
+        assert_eq!(soil_to_light.get(81), 74);
+        assert_eq!(almanac.map("soil", "soil"), Ok(Map::default()));
+        assert_eq!(
+            almanac.map("location", "seed"),
+            Err(PathError::NoPath("location".into(), "seed".into()))
+        );
+        assert_eq!(
+            almanac.map("seed", "gold"),
+            Err(PathError::UnknownCategory("gold".into()))
+        );
+
+        let input = format!("{INPUT}\nseed-to-water map:\n1 2 3\n");
+        let almanac = parse_part1(&input).unwrap();
+        assert_eq!(
+            part1(&almanac),
+            Err(PathError::Ambiguous("seed".into(), "location".into()))
+        );
+        assert!(almanac.map("water", "location").is_ok());
+    }
+
+    #[test]
+    fn reordered_and_extra_stages() {
+        let mut sections: Vec<&str> = INPUT.trim_end().split("\n\n").collect();
+        sections[1..].reverse();
+        sections.push("location-to-treasure map:\n0 0 100");
+        let input = sections.join("\n\n");
+
+        assert_eq!(part1(&parse_part1(&input).unwrap()), Ok(35));
+        assert_eq!(part2(&parse_part2(&input).unwrap()), Ok(46));
+        let almanac = parse_part1(&input).unwrap();
+        assert_eq!(almanac.map("seed", "treasure").unwrap().get(13), 35);
+    }
+
+    #[test]
+    fn parse_error() {
+        let input = INPUT.replace("52 50 48", "52 50 49");
+
+        assert_eq!(
+            parse_part1(&input).unwrap_err().to_string(),
+            "day 5, line 5, column 1: overlaps with source range 98..100 (at \"52 50 49\")"
+        );
+
+        let input = INPUT.replace("water-to-light", "water to light");
+        assert_eq!(
+            parse_part1(&input).unwrap_err().message,
+            "expected \"-to-\""
+        );
     }
 
     #[test]
     fn test_map_ranges() {
-        let map = Map::from([(2..5, 7..10), (7..10, 2..5)]);
-        assert_eq!(map_ranges(0..2, &map), vec![0..2]);
-        assert_eq!(map_ranges(0..5, &map), vec![0..2, 7..10]);
-        assert_eq!(map_ranges(3..8, &map), vec![8..10, 5..7, 2..3]);
-        assert_eq!(map_ranges(7..17, &map), vec![2..5, 10..17]);
-        assert_eq!(map_ranges(11..17, &map), vec![11..17]);
+        let map = Map::from_iter([(2..5, 7), (7..10, 2)]);
+        assert_eq!(map.map_range(0..2), vec![0..2]);
+        assert_eq!(map.map_range(0..5), vec![0..2, 7..10]);
+        assert_eq!(map.map_range(3..8), vec![8..10, 5..7, 2..3]);
+        assert_eq!(map.map_range(7..17), vec![2..5, 10..17]);
+        assert_eq!(map.map_range(11..17), vec![11..17]);
     }
 }
@@ -1,7 +1,12 @@
+use crate::number::{self, Int, Overflow};
+use crate::parse::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
-use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::iter;
+use std::ops::RangeInclusive;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 enum Card {
@@ -21,23 +26,35 @@ enum Card {
 }
 
 impl Card {
-    fn cmp(&self, other: &Self, part2: bool) -> Ordering {
-        if part2 {
-            match (self, other) {
-                (Card::CJ, Card::CJ) => Ordering::Equal,
-                (Card::CJ, _) => Ordering::Less,
-                (_, Card::CJ) => Ordering::Greater,
-                _ => Ord::cmp(self, other),
-            }
-        } else {
-            Ord::cmp(self, other)
-        }
+    const ALL: [Card; 13] = [
+        Card::C2,
+        Card::C3,
+        Card::C4,
+        Card::C5,
+        Card::C6,
+        Card::C7,
+        Card::C8,
+        Card::C9,
+        Card::CT,
+        Card::CJ,
+        Card::CQ,
+        Card::CK,
+        Card::CA,
+    ];
+}
+
+impl Display for Card {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let c = "23456789TJQKA".as_bytes()[*self as usize];
+        write!(f, "{}", c as char)
     }
 }
 
-impl From<char> for Card {
-    fn from(value: char) -> Self {
-        match value {
+impl TryFrom<char> for Card {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Ok(match value {
             '2' => Card::C2,
             '3' => Card::C3,
             '4' => Card::C4,
@@ -51,130 +68,456 @@ impl From<char> for Card {
             'Q' => Card::CQ,
             'K' => Card::CK,
             'A' => Card::CA,
-            _ => panic!("Unknown Card"),
-        }
+            _ => return Err(format!("unknown card {value:?}")),
+        })
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl Display for Suit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let c = "cdhs".as_bytes()[*self as usize];
+        write!(f, "{}", c as char)
+    }
+}
+
+impl TryFrom<char> for Suit {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Ok(match value {
+            'c' => Suit::Clubs,
+            'd' => Suit::Diamonds,
+            'h' => Suit::Hearts,
+            's' => Suit::Spades,
+            _ => return Err(format!("unknown suit {value:?}")),
+        })
+    }
+}
+
+// Straights and flushes are only recognised by house rulesets.
+#[allow(dead_code)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Type {
     HighCard,
     OnePair,
     TwoPair,
     ThreeOfAKind,
+    Straight,
+    Flush,
     FullHouse,
     FourOfAKind,
+    StraightFlush,
     FiveOfAKind,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-struct Hand(Card, Card, Card, Card, Card);
+impl Type {
+    /// The groups of equal cards the type needs, largest first.
+    fn groups(self) -> &'static [usize] {
+        match self {
+            Type::FiveOfAKind => &[5],
+            Type::FourOfAKind => &[4],
+            Type::FullHouse => &[3, 2],
+            Type::ThreeOfAKind => &[3],
+            Type::TwoPair => &[2, 2],
+            Type::OnePair => &[2],
+            Type::HighCard | Type::Straight | Type::Flush | Type::StraightFlush => &[],
+        }
+    }
+}
 
-impl Hand {
-    fn get_type(&self, part2: bool) -> Type {
-        let mut counts = HashMap::new();
-        let mut jokers = 0;
-
-        for &card in &[self.0, self.1, self.2, self.3, self.4] {
-            if part2 && card == Card::CJ {
-                jokers += 1;
-            } else {
-                *counts.entry(card).or_insert(0) += 1;
+/// The rules a game is scored by.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Ruleset {
+    /// All cards, weakest first.
+    order: Vec<Card>,
+    /// Cards that stand in for whichever card makes the best hand.
+    wild: Vec<Card>,
+    /// The recognised hand types, weakest first.
+    types: Vec<Type>,
+}
+
+impl Ruleset {
+    fn standard() -> Self {
+        Self {
+            order: Card::ALL.to_vec(),
+            wild: Vec::new(),
+            types: vec![
+                Type::HighCard,
+                Type::OnePair,
+                Type::TwoPair,
+                Type::ThreeOfAKind,
+                Type::FullHouse,
+                Type::FourOfAKind,
+                Type::FiveOfAKind,
+            ],
+        }
+    }
+
+    /// Jacks become jokers: wild, but the weakest card on ties.
+    fn jokers() -> Self {
+        let mut order = vec![Card::CJ];
+        order.extend(Card::ALL.into_iter().filter(|&card| card != Card::CJ));
+        Self {
+            order,
+            wild: vec![Card::CJ],
+            ..Self::standard()
+        }
+    }
+
+    fn rank(&self, card: Card) -> usize {
+        self.order
+            .iter()
+            .position(|&c| c == card)
+            .unwrap_or_else(|| panic!("{card:?} is missing from the card order"))
+    }
+
+    fn is_wild(&self, card: Card) -> bool {
+        self.wild.contains(&card)
+    }
+
+    /// Returns the strongest type in the table the hand can make.
+    fn get_type(&self, hand: &Hand) -> Type {
+        self.types
+            .iter()
+            .rev()
+            .copied()
+            .find(|&kind| self.matches(hand, kind))
+            .unwrap_or(Type::HighCard)
+    }
+
+    /// Straights and flushes need the whole hand, whatever its size.
+    fn matches(&self, hand: &Hand, kind: Type) -> bool {
+        let cards = hand.cards();
+        let (wild, fixed): (Vec<usize>, Vec<usize>) =
+            (0..cards.len()).partition(|&i| self.is_wild(cards[i]));
+        let fixed_cards = fixed.iter().map(|&i| cards[i]).collect_vec();
+
+        match kind {
+            Type::Straight => self.is_straight(&fixed_cards, cards.len()),
+            Type::Flush => self.is_flush(hand, &fixed),
+            Type::StraightFlush => {
+                self.is_straight(&fixed_cards, cards.len()) && self.is_flush(hand, &fixed)
+            }
+            _ => {
+                let mut counts = fixed_cards.iter().counts().into_values().collect_vec();
+                counts.sort_unstable_by(|a, b| b.cmp(a));
+                let missing: usize = kind
+                    .groups()
+                    .iter()
+                    .zip(counts.into_iter().chain(iter::repeat(0)))
+                    .map(|(&needed, count)| needed.saturating_sub(count))
+                    .sum();
+                missing <= wild.len()
             }
         }
+    }
+
+    /// Whether wild cards can fill the fixed cards up to `len` consecutive ranks.
+    fn is_straight(&self, fixed: &[Card], len: usize) -> bool {
+        let ranks = fixed.iter().map(|&card| self.rank(card)).collect_vec();
+        if !ranks.iter().all_unique() || len > self.order.len() {
+            return false;
+        }
+        match ranks.iter().minmax().into_option() {
+            Some((min, max)) => max - min < len,
+            None => true,
+        }
+    }
+
+    /// Replaces every wild card with a concrete card that makes the best type.
+    fn substitute(&self, hand: &Hand) -> (Hand, Type) {
+        let kind = self.get_type(hand);
+        let cards = hand.cards();
+        let fixed = cards
+            .iter()
+            .copied()
+            .filter(|&card| !self.is_wild(card))
+            .collect_vec();
+        let mut spares = self
+            .order
+            .iter()
+            .rev()
+            .copied()
+            .filter(|&card| !self.is_wild(card) && !fixed.contains(&card));
 
-        let mut counts = counts.values().copied().collect_vec();
-        if part2 {
-            counts.sort();
-            if counts.is_empty() {
-                counts = vec![jokers];
-            } else {
-                *counts.last_mut().unwrap() += jokers;
+        let fills = match kind {
+            Type::Straight | Type::StraightFlush => {
+                let ranks = fixed.iter().map(|&card| self.rank(card));
+                let start = ranks
+                    .min()
+                    .unwrap_or(self.order.len())
+                    .min(self.order.len() - cards.len());
+                self.order[start..start + cards.len()]
+                    .iter()
+                    .copied()
+                    .filter(|card| !fixed.contains(card))
+                    .collect_vec()
+            }
+            _ => {
+                let mut groups = fixed.iter().copied().counts().into_iter().collect_vec();
+                groups.sort_by_key(|&(card, count)| Reverse((count, self.rank(card))));
+                let mut fills = Vec::new();
+                for (i, &needed) in kind.groups().iter().enumerate() {
+                    let (card, count) = match groups.get(i) {
+                        Some(&group) => group,
+                        None => (spares.next().unwrap_or(Card::CA), 0),
+                    };
+                    fills.extend(iter::repeat_n(card, needed.saturating_sub(count)));
+                }
+                fills
+            }
+        };
+
+        let suit = hand.suits().and_then(|suits| {
+            (0..cards.len())
+                .find(|&i| !self.is_wild(cards[i]))
+                .map(|i| suits[i])
+        });
+        let mut fills = fills.into_iter().chain(spares);
+        let mut substituted = hand.clone();
+        for (i, card) in cards.iter().enumerate() {
+            if self.is_wild(*card) {
+                substituted.cards[i] = fills.next().unwrap_or(*card);
+                if let (Some(suits), Some(suit), Type::Flush | Type::StraightFlush) =
+                    (&mut substituted.suits, suit, kind)
+                {
+                    suits[i] = suit;
+                }
             }
         }
-        counts.sort();
-        counts.reverse();
+        (substituted, kind)
+    }
 
-        match counts[..] {
-            [5, ..] => Type::FiveOfAKind,
-            [4, ..] => Type::FourOfAKind,
-            [3, 2, ..] => Type::FullHouse,
-            [3, ..] => Type::ThreeOfAKind,
-            [2, 2, ..] => Type::TwoPair,
-            [2, ..] => Type::OnePair,
-            [..] => Type::HighCard,
+    /// Whether the cards at `fixed` share a suit; wild cards take whichever suit is needed.
+    fn is_flush(&self, hand: &Hand, fixed: &[usize]) -> bool {
+        hand.suits()
+            .is_some_and(|suits| fixed.iter().map(|&i| suits[i]).all_equal())
+    }
+
+    /// A sort key ordering hands by type, then card by card.
+    fn strength(&self, hand: &Hand) -> (Option<usize>, Vec<usize>) {
+        let kind = self.get_type(hand);
+        (
+            self.types.iter().position(|&t| t == kind),
+            hand.cards().iter().map(|&card| self.rank(card)).collect(),
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Hand {
+    cards: Vec<Card>,
+    suits: Option<Vec<Suit>>,
+}
+
+impl Hand {
+    const SIZES: RangeInclusive<usize> = 3..=7;
+
+    fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// The suit of each card, if the hand was dealt with suits.
+    fn suits(&self) -> Option<&[Suit]> {
+        self.suits.as_deref()
+    }
+
+    /// Parses cards such as `"32T3K"`, or `"3h2sTd3cKs"` with suits.
+    fn parse<'a>(source: &Source<'a>, text: &'a str) -> Result<Self, ParseError> {
+        let mut cards = Vec::new();
+        let mut suits = Vec::new();
+        let mut chars = Source::chars(text).peekable();
+        while let Some((c, at)) = chars.next() {
+            cards.push(Card::try_from(c).map_err(|e| source.error(at, e))?);
+            if let Some((suit, at)) = chars.next_if(|&(c, _)| c.is_ascii_lowercase()) {
+                suits.push(Suit::try_from(suit).map_err(|e| source.error(at, e))?);
+            }
+        }
+
+        if !Self::SIZES.contains(&cards.len()) {
+            return Err(source.error(
+                text,
+                format!(
+                    "expected {} to {} cards, found {}",
+                    Self::SIZES.start(),
+                    Self::SIZES.end(),
+                    cards.len()
+                ),
+            ));
+        }
+        if suits.is_empty() {
+            return Ok(cards.into());
+        }
+        if suits.len() != cards.len() {
+            return Err(source.error(text, "mixes suited and unsuited cards"));
         }
+        if !cards.iter().zip(&suits).all_unique() {
+            return Err(source.error(text, "contains the same card twice"));
+        }
+        Ok(Self {
+            cards,
+            suits: Some(suits),
+        })
     }
+}
 
-    fn cmp(&self, other: &Self, part2: bool) -> Ordering {
-        self.0
-            .cmp(&other.0, part2)
-            .then(self.1.cmp(&other.1, part2))
-            .then(self.2.cmp(&other.2, part2))
-            .then(self.3.cmp(&other.3, part2))
-            .then(self.4.cmp(&other.4, part2))
+impl From<Vec<Card>> for Hand {
+    fn from(cards: Vec<Card>) -> Self {
+        Self { cards, suits: None }
     }
 }
 
-impl From<(Card, Card, Card, Card, Card)> for Hand {
-    fn from(value: (Card, Card, Card, Card, Card)) -> Self {
-        Self(value.0, value.1, value.2, value.3, value.4)
+impl Display for Hand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, card) in self.cards.iter().enumerate() {
+            write!(f, "{card}")?;
+            if let Some(suits) = &self.suits {
+                write!(f, "{}", suits[i])?;
+            }
+        }
+        Ok(())
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 struct Round(Hand, u32);
 
-impl Round {
-    fn cmp(&self, other: &Self, part2: bool) -> Ordering {
-        self.0
-            .get_type(part2)
-            .cmp(&other.0.get_type(part2))
-            .then(self.0.cmp(&other.0, part2))
+/// One line of the ranked table a score is summed from.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Row {
+    rank: u32,
+    hand: Hand,
+    substituted: Hand,
+    kind: Type,
+    bid: u32,
+    winnings: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Table(Vec<Row>);
+
+impl Table {
+    fn new(input: &[Round], rules: &Ruleset) -> Self {
+        Self(
+            ranked(input, rules)
+                .into_iter()
+                .zip(1..)
+                .map(|(Round(hand, bid), rank)| {
+                    let (substituted, kind) = rules.substitute(hand);
+                    Row {
+                        rank,
+                        hand: hand.clone(),
+                        substituted,
+                        kind,
+                        bid: *bid,
+                        winnings: u64::from(rank) * u64::from(*bid),
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    fn total(&self) -> Result<Int, Overflow> {
+        number::sum(self.0.iter().map(|row| Ok(Int::from(row.winnings))))
     }
 }
 
+impl Display for Table {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let width = self
+            .0
+            .iter()
+            .map(|row| row.hand.to_string().len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+        writeln!(
+            f,
+            "{:>4}  {:<width$}  {:<width$}  {:<13}  {:>5}  {:>8}",
+            "rank", "hand", "best", "type", "bid", "winnings"
+        )?;
+        for row in &self.0 {
+            writeln!(
+                f,
+                "{:>4}  {:<width$}  {:<width$}  {:<13}  {:>5}  {:>8}",
+                row.rank,
+                row.hand.to_string(),
+                row.substituted.to_string(),
+                format!("{:?}", row.kind),
+                row.bid,
+                row.winnings
+            )?;
+        }
+        match self.total() {
+            Ok(total) => write!(f, "total {total}"),
+            Err(e) => write!(f, "total unknown: {e}"),
+        }
+    }
+}
+
+/// The rounds from the weakest hand to the strongest.
+fn ranked<'a>(input: &'a [Round], rules: &Ruleset) -> Vec<&'a Round> {
+    let mut input = input.iter().collect_vec();
+    input.sort_by_cached_key(|round| rules.strength(&round.0));
+    input
+}
+
+fn winnings(input: &[Round], rules: &Ruleset) -> Result<Int, Overflow> {
+    number::sum(
+        ranked(input, rules)
+            .into_iter()
+            .zip(1u64..)
+            .map(|(Round(_, bid), rank)| Ok(Int::from(rank * u64::from(*bid)))),
+    )
+}
+
+/// Prints the ranked table behind a day 7 score, with the cards the jokers
+/// stand for when `jokers` is set.
+pub fn audit_hands(input: &str, jokers: bool) -> Result<String, Box<dyn Error>> {
+    let rules = if jokers {
+        Ruleset::jokers()
+    } else {
+        Ruleset::standard()
+    };
+    Ok(format!("{}\n", Table::new(&parse(input)?, &rules)))
+}
+
 #[aoc_generator(day7)]
-fn parse(input: &str) -> Vec<Round> {
+fn parse(input: &str) -> Result<Vec<Round>, ParseError> {
+    let source = Source::new(7, input);
     input
         .lines()
         .map(|line| {
-            let (hand, bid) = line.split_once(' ').unwrap();
-            let hand = hand
-                .chars()
-                .map(|c| c.into())
-                .collect_tuple::<(Card, Card, Card, Card, Card)>()
-                .unwrap()
-                .into();
-            Round(hand, bid.trim().parse().unwrap())
+            let (hand, bid) = source.split_once(line, " ")?;
+            let hand = Hand::parse(&source, hand)?;
+            Ok(Round(hand, source.parse(bid.trim())?))
         })
-        .collect_vec()
+        .collect()
 }
 
 #[aoc(day7, part1)]
-fn part1(input: &[Round]) -> u32 {
-    let mut input = input.to_vec();
-    input.sort_by(|a, b| a.cmp(b, false));
-    input
-        .iter()
-        .enumerate()
-        .fold(0, |acc, (rank, round)| acc + (rank as u32 + 1) * round.1)
+fn part1(input: &[Round]) -> Result<Int, Overflow> {
+    winnings(input, &Ruleset::standard())
 }
 
 #[aoc(day7, part2)]
-fn part2(input: &[Round]) -> u32 {
-    let mut input = input.to_vec();
-    input.sort_by(|a, b| a.cmp(b, true));
-    input
-        .iter()
-        .enumerate()
-        .fold(0, |acc, (rank, round)| acc + (rank as u32 + 1) * round.1)
+fn part2(input: &[Round]) -> Result<Int, Overflow> {
+    winnings(input, &Ruleset::jokers())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use std::cmp::Ordering;
 
     #[test]
     fn parse_example() {
@@ -188,16 +531,65 @@ mod tests {
 
         assert_eq!(
             parse(input),
-            vec![
-                Round(Hand(Card::C3, Card::C2, Card::CT, Card::C3, Card::CK), 765),
-                Round(Hand(Card::CT, Card::C5, Card::C5, Card::CJ, Card::C5), 684),
-                Round(Hand(Card::CK, Card::CK, Card::C6, Card::C7, Card::C7), 28),
-                Round(Hand(Card::CK, Card::CT, Card::CJ, Card::CJ, Card::CT), 220),
-                Round(Hand(Card::CQ, Card::CQ, Card::CQ, Card::CJ, Card::CA), 483),
-            ]
+            Ok(vec![
+                Round(
+                    Hand::from(vec![Card::C3, Card::C2, Card::CT, Card::C3, Card::CK]),
+                    765
+                ),
+                Round(
+                    Hand::from(vec![Card::CT, Card::C5, Card::C5, Card::CJ, Card::C5]),
+                    684
+                ),
+                Round(
+                    Hand::from(vec![Card::CK, Card::CK, Card::C6, Card::C7, Card::C7]),
+                    28
+                ),
+                Round(
+                    Hand::from(vec![Card::CK, Card::CT, Card::CJ, Card::CJ, Card::CT]),
+                    220
+                ),
+                Round(
+                    Hand::from(vec![Card::CQ, Card::CQ, Card::CQ, Card::CJ, Card::CA]),
+                    483
+                ),
+            ])
         )
     }
 
+    #[test]
+    fn parse_error() {
+        let input = indoc! {"
+            32T3K 765
+            T55X5 684
+        "};
+
+        assert_eq!(
+            parse(input).unwrap_err().to_string(),
+            "day 7, line 2, column 4: unknown card 'X' (at \"X\")"
+        );
+
+        let input = indoc! {"
+            32 765
+        "};
+
+        assert_eq!(
+            parse(input).unwrap_err().to_string(),
+            "day 7, line 1, column 1: expected 3 to 7 cards, found 2 (at \"32\")"
+        );
+
+        let errors = ["3h2x 1", "3h2K 1", "3h2h3h 1", "32T3K9A2 1"]
+            .map(|input| parse(input).unwrap_err().message);
+        assert_eq!(
+            errors,
+            [
+                "unknown suit 'x'",
+                "mixes suited and unsuited cards",
+                "contains the same card twice",
+                "expected 3 to 7 cards, found 8",
+            ]
+        );
+    }
+
     #[test]
     fn part1_example() {
         let input = indoc! {"
@@ -208,7 +600,7 @@ mod tests {
             QQQJA 483
         "};
 
-        assert_eq!(part1(&parse(input)), 6440);
+        assert_eq!(part1(&parse(input).unwrap()), Ok(Int::from(6440u64)));
     }
 
     #[test]
@@ -221,6 +613,142 @@ mod tests {
             QQQJA 483
         "};
 
-        assert_eq!(part2(&parse(input)), 5905);
+        assert_eq!(part2(&parse(input).unwrap()), Ok(Int::from(5905u64)));
+    }
+
+    fn hand(cards: &str) -> Hand {
+        Hand::parse(&Source::new(7, cards), cards).unwrap()
+    }
+
+    #[test]
+    fn any_hand_size() {
+        let rules = Ruleset::standard();
+
+        assert_eq!(
+            parse("Ah3s9d 1\nKKQQ3A7 2"),
+            Ok(vec![
+                Round(
+                    Hand {
+                        cards: vec![Card::CA, Card::C3, Card::C9],
+                        suits: Some(vec![Suit::Hearts, Suit::Spades, Suit::Diamonds]),
+                    },
+                    1
+                ),
+                Round(hand("KKQQ3A7"), 2),
+            ])
+        );
+        assert_eq!(rules.get_type(&hand("K3K")), Type::OnePair);
+        assert_eq!(rules.get_type(&hand("KKK")), Type::ThreeOfAKind);
+        assert_eq!(rules.get_type(&hand("KKQQ3A7")), Type::TwoPair);
+        assert_eq!(rules.get_type(&hand("KKQQQ77")), Type::FullHouse);
+        assert_eq!(rules.get_type(&hand("KKKKK77")), Type::FiveOfAKind);
+        assert_eq!(Ruleset::jokers().get_type(&hand("JJJ")), Type::ThreeOfAKind);
+    }
+
+    #[test]
+    fn substitute() {
+        let rules = Ruleset::jokers();
+        let explain = |cards: &str| {
+            let (substituted, kind) = rules.substitute(&hand(cards));
+            (substituted.to_string(), kind)
+        };
+
+        assert_eq!(explain("T55J5"), ("T5555".into(), Type::FourOfAKind));
+        assert_eq!(explain("KTJJT"), ("KTTTT".into(), Type::FourOfAKind));
+        assert_eq!(explain("JJJJJ"), ("AAAAA".into(), Type::FiveOfAKind));
+        assert_eq!(explain("2J3J4"), ("24344".into(), Type::ThreeOfAKind));
+        assert_eq!(explain("32T3K"), ("32T3K".into(), Type::OnePair));
+
+        let mut rules = Ruleset::jokers();
+        rules.types = vec![
+            Type::HighCard,
+            Type::Straight,
+            Type::Flush,
+            Type::StraightFlush,
+        ];
+        let (substituted, kind) = rules.substitute(&hand("2hJsKhQhJc"));
+        assert_eq!(
+            (substituted.to_string(), kind),
+            ("2hAhKhQhTh".into(), Type::Flush)
+        );
+        let (substituted, kind) = rules.substitute(&hand("9hJsKhQhTh"));
+        assert_eq!(
+            (substituted.to_string(), kind),
+            ("9hAhKhQhTh".into(), Type::StraightFlush)
+        );
+
+        let concrete = |rules: &Ruleset| Ruleset {
+            wild: Vec::new(),
+            ..rules.clone()
+        };
+        for cards in ["JJ234", "AJ2J3", "J4J4J", "KK2JA", "Ts9sJhJd6s", "7J8"] {
+            let (substituted, kind) = rules.substitute(&hand(cards));
+            assert_eq!(concrete(&rules).get_type(&substituted), kind, "{cards}");
+            let jokers = Ruleset::jokers();
+            let (substituted, kind) = jokers.substitute(&hand(cards));
+            assert_eq!(concrete(&jokers).get_type(&substituted), kind, "{cards}");
+        }
+    }
+
+    #[test]
+    fn table() {
+        let input = indoc! {"
+            32T3K 765
+            T55J5 684
+            KK677 28
+            KTJJT 220
+            QQQJA 483
+        "};
+        let table = Table::new(&parse(input).unwrap(), &Ruleset::jokers());
+
+        assert_eq!(
+            table.to_string(),
+            indoc! {"
+                rank  hand   best   type             bid  winnings
+                   1  32T3K  32T3K  OnePair          765       765
+                   2  KK677  KK677  TwoPair           28        56
+                   3  T55J5  T5555  FourOfAKind      684      2052
+                   4  QQQJA  QQQQA  FourOfAKind      483      1932
+                   5  KTJJT  KTTTT  FourOfAKind      220      1100
+                total 5905"}
+        );
+    }
+
+    #[test]
+    fn house_rules() {
+        let mut rules = Ruleset::jokers();
+        rules.wild.push(Card::C2);
+        rules.types.insert(4, Type::Straight);
+
+        assert_eq!(rules.get_type(&hand("2J345")), Type::Straight);
+        assert_eq!(rules.get_type(&hand("2J335")), Type::FourOfAKind);
+        assert_eq!(rules.get_type(&hand("T9K8Q")), Type::Straight);
+        assert_eq!(rules.get_type(&hand("T9J8A")), Type::OnePair);
+        assert_eq!(rules.get_type(&hand("T9J88")), Type::ThreeOfAKind);
+        assert_eq!(rules.get_type(&hand("T9J7A")), Type::OnePair);
+        assert_eq!(rules.get_type(&hand("TQJ2A")), Type::Straight);
+        assert_eq!(Ruleset::standard().get_type(&hand("T9K8Q")), Type::HighCard);
+        assert_eq!(Ruleset::standard().get_type(&hand("2J345")), Type::HighCard);
+        assert_eq!(
+            rules
+                .strength(&hand("T9K8Q"))
+                .cmp(&rules.strength(&hand("QQQ89"))),
+            Ordering::Greater
+        );
+
+        rules.types.insert(5, Type::Flush);
+        rules.types.insert(8, Type::StraightFlush);
+        assert_eq!(rules.get_type(&hand("Ah9h3hKhJs")), Type::Flush);
+        assert_eq!(rules.get_type(&hand("Ah9h3hKh")), Type::Flush);
+        assert_eq!(rules.get_type(&hand("TsQsJh2dAs")), Type::StraightFlush);
+        assert_eq!(rules.get_type(&hand("TQJ2A")), Type::Straight);
+
+        rules.order.reverse();
+        assert_eq!(
+            rules
+                .strength(&hand("AAK44"))
+                .cmp(&rules.strength(&hand("33TTT"))),
+            Ordering::Less
+        );
     }
 }
@@ -1,96 +1,406 @@
+use crate::cycle::{find_cycle, Cycle, History};
+use crate::number::{self, Int, Overflow};
+use crate::parse::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
+use num_integer::Integer;
+use num_traits::{CheckedSub, One, ToPrimitive, Zero};
 use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// The nodes of a map, interned into dense indices.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Network {
+    directions: Vec<char>,
+    names: Vec<String>,
+    indices: HashMap<String, usize>,
+    /// The left and right neighbour of each node.
+    edges: Vec<[usize; 2]>,
+}
+
+impl Network {
+    fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    fn index(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    fn name(&self, node: usize) -> &str {
+        &self.names[node]
+    }
+
+    fn names(&self, nodes: impl IntoIterator<Item = usize>) -> Vec<String> {
+        nodes
+            .into_iter()
+            .map(|node| self.name(node).to_string())
+            .collect()
+    }
+
+    fn next(&self, node: usize, direction: char) -> usize {
+        match direction {
+            'L' => self.edges[node][0],
+            _ => self.edges[node][1],
+        }
+    }
+
+    /// Returns every node whose name ends with `suffix`.
+    fn ending_with(&self, suffix: char) -> Vec<usize> {
+        (0..self.len())
+            .filter(|&node| self.name(node).ends_with(suffix))
+            .collect()
+    }
+}
 
 #[aoc_generator(day8)]
-fn parse(input: &str) -> (Vec<char>, HashMap<String, (String, String)>) {
-    let directions = input.lines().next().unwrap().chars().collect_vec();
-    let map = input
+fn parse(input: &str) -> Result<Network, ParseError> {
+    let source = Source::new(8, input);
+    let first_line = input.lines().next().unwrap_or_default();
+    let directions = Source::chars(first_line)
+        .map(|(c, at)| match c {
+            'L' | 'R' => Ok(c),
+            _ => Err(source.error(at, format!("unknown direction {c:?}"))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if directions.is_empty() {
+        return Err(source.error(first_line, "expected directions"));
+    }
+
+    let nodes = input
         .lines()
         .skip(2)
         .map(|line| {
-            let (key, value) = line.split_once(" = ").unwrap();
-            let value = value
-                .split_once(", ")
-                .map(|(left, right)| {
-                    (
-                        left.split_at(1).1.to_string(),
-                        right.split_at(3).0.to_string(),
-                    )
-                })
-                .unwrap();
-            (key.to_string(), value)
+            let (key, value) = source.split_once(line, " = ")?;
+            let value = source.strip_prefix(value, "(")?;
+            let value = source.strip_suffix(value, ")")?;
+            Ok((key, source.split_once(value, ", ")?))
         })
-        .collect();
-    (directions, map)
+        .collect::<Result<Vec<_>, ParseError>>()?;
+
+    let mut indices = HashMap::new();
+    for &(key, _) in &nodes {
+        if indices.insert(key.to_string(), indices.len()).is_some() {
+            return Err(source.error(key, "duplicate node"));
+        }
+    }
+
+    let lookup = |node: &str| {
+        indices
+            .get(node)
+            .copied()
+            .ok_or_else(|| source.error(node, "unknown node"))
+    };
+    let edges = nodes
+        .iter()
+        .map(|(_, (left, right))| Ok([lookup(left)?, lookup(right)?]))
+        .collect::<Result<Vec<_>, ParseError>>()?;
+
+    Ok(Network {
+        directions,
+        names: nodes.iter().map(|(key, _)| key.to_string()).collect(),
+        indices,
+        edges,
+    })
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum StepsError {
+    Missing(&'static str),
+    /// The instructions never lead from the start to the end.
+    Unreachable,
+}
+
+impl Display for StepsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StepsError::Missing(name) => write!(f, "no node named {name}"),
+            StepsError::Unreachable => write!(f, "the instructions never reach ZZZ"),
+        }
+    }
 }
 
+impl Error for StepsError {}
+
 #[aoc(day8, part1)]
-fn part1(input: &(Vec<char>, HashMap<String, (String, String)>)) -> u64 {
-    get_steps(&input.0, &input.1, &"AAA".to_string(), |location| {
-        location == "ZZZ"
-    })
+fn part1(input: &Network) -> Result<u64, StepsError> {
+    let node = |name| input.index(name).ok_or(StepsError::Missing(name));
+    let (start, end) = (node("AAA")?, node("ZZZ")?);
+    get_steps(input, start, |node| node == end).ok_or(StepsError::Unreachable)
 }
 
 #[aoc(day8, part2)]
-fn part2(input: &(Vec<char>, HashMap<String, (String, String)>)) -> u64 {
-    input
-        .1
-        .keys()
-        .filter(|key| key.ends_with('A'))
-        .map(|location| {
-            get_steps(&input.0, &input.1, location, |location| {
-                location.ends_with('Z')
+fn part2(input: &Network) -> Result<Int, GhostError> {
+    if input.ending_with('A').is_empty() {
+        return Err(GhostError::NoGhosts);
+    }
+    let hits = Analysis::new(input)
+        .ghosts
+        .into_iter()
+        .map(|ghost| ghost.hits)
+        .collect_vec();
+    first_common_hit(&hits)?.ok_or(GhostError::Never)
+}
+
+/// How the ghosts starting on `..A` nodes move through a network.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Analysis {
+    pub ghosts: Vec<Ghost>,
+    /// Nodes that no ghost ever steps on.
+    pub dead: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Ghost {
+    pub start: String,
+    /// Nodes reachable by any sequence of turns, not just the instructions.
+    pub reachable: Vec<String>,
+    /// Steps before the (node, instruction index) states start repeating.
+    pub tail: usize,
+    pub cycle: usize,
+    /// The `..Z` nodes visited on the cycle.
+    pub cycle_ends: Vec<String>,
+    hits: Hits,
+}
+
+impl Analysis {
+    fn new(network: &Network) -> Self {
+        let mut is_end = vec![false; network.len()];
+        for node in network.ending_with('Z') {
+            is_end[node] = true;
+        }
+
+        let mut visited = vec![false; network.len()];
+        let ghosts = network
+            .ending_with('A')
+            .into_iter()
+            .map(|start| {
+                let history = walk(network, start);
+                let Cycle { prefix, period } = history.cycle;
+                for &(node, _) in &history.states {
+                    visited[node] = true;
+                }
+                Ghost {
+                    start: network.name(start).to_string(),
+                    reachable: network.names(reachable(network, start)),
+                    tail: prefix,
+                    cycle: period,
+                    cycle_ends: network.names(
+                        history.states[prefix..]
+                            .iter()
+                            .map(|&(node, _)| node)
+                            .filter(|&node| is_end[node])
+                            .unique(),
+                    ),
+                    hits: Hits::new(&history, &is_end),
+                }
             })
-        })
-        .fold(1, lcm)
-}
-
-fn get_steps(
-    directions: &[char],
-    map: &HashMap<String, (String, String)>,
-    start: &String,
-    is_end: impl Fn(&String) -> bool,
-) -> u64 {
-    let mut current_location = start;
-    let mut steps = 0;
-    for direction in directions.iter().cycle() {
-        current_location = match direction {
-            'L' => &map.get(current_location).unwrap().0,
-            'R' => &map.get(current_location).unwrap().1,
-            _ => panic!("Unknown direction"),
-        };
-        steps += 1;
-        if is_end(current_location) {
-            break;
+            .collect();
+
+        Self {
+            ghosts,
+            dead: network.names((0..network.len()).filter(|&node| !visited[node])),
+        }
+    }
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for ghost in &self.ghosts {
+            writeln!(
+                f,
+                "{}: {} reachable, tail {}, cycle {}, ends on cycle [{}]",
+                ghost.start,
+                ghost.reachable.len(),
+                ghost.tail,
+                ghost.cycle,
+                ghost.cycle_ends.join(", ")
+            )?;
+        }
+        write!(f, "dead: [{}]", self.dead.join(", "))
+    }
+}
+
+/// Analyses how the ghosts move through a day 8 network.
+pub fn analyse_ghosts(input: &str) -> Result<Analysis, Box<dyn Error>> {
+    Ok(Analysis::new(&parse(input)?))
+}
+
+/// Follows the instructions from `start` until a (node, instruction index) state repeats.
+fn walk(network: &Network, start: usize) -> History<(usize, usize)> {
+    let len = network.directions.len();
+    find_cycle((start, 0), |&(node, i)| {
+        (network.next(node, network.directions[i]), (i + 1) % len)
+    })
+}
+
+/// Returns every node reachable from `start`, in index order.
+fn reachable(network: &Network, start: usize) -> Vec<usize> {
+    let mut seen = vec![false; network.len()];
+    let mut stack = vec![start];
+    seen[start] = true;
+    while let Some(node) = stack.pop() {
+        for next in network.edges[node] {
+            if !seen[next] {
+                seen[next] = true;
+                stack.push(next);
+            }
+        }
+    }
+    (0..network.len()).filter(|&node| seen[node]).collect()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum GhostError {
+    NoGhosts,
+    Never,
+    Overflow(Overflow),
+}
+
+impl From<Overflow> for GhostError {
+    fn from(e: Overflow) -> Self {
+        GhostError::Overflow(e)
+    }
+}
+
+impl Display for GhostError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GhostError::NoGhosts => write!(f, "no ghosts: the network has no nodes ending in A"),
+            GhostError::Never => write!(f, "never: the ghosts are never all on Z nodes at once"),
+            GhostError::Overflow(e) => write!(f, "{e}"),
         }
     }
-    steps
 }
 
-fn lcm(a: u64, b: u64) -> u64 {
-    let mut u = a;
-    let mut v = b;
-    if v == 0 {
-        return u;
+impl Error for GhostError {}
+
+/// The steps at which one ghost stands on an end node: every step in `tail`,
+/// and every step from `start` on that is congruent to one of `residues` modulo `period`.
+#[derive(Debug, PartialEq, Eq)]
+struct Hits {
+    tail: Vec<u64>,
+    start: u64,
+    period: u64,
+    residues: Vec<u64>,
+}
+
+impl Hits {
+    fn new(history: &History<(usize, usize)>, is_end: &[bool]) -> Self {
+        let Cycle { prefix, period } = history.cycle;
+        let (tail, cycle): (Vec<_>, Vec<_>) = history
+            .states
+            .iter()
+            .positions(|&(node, _)| is_end[node])
+            .filter(|&step| step > 0)
+            .partition(|&step| step < prefix);
+
+        Self {
+            tail: tail.into_iter().map(|step| step as u64).collect(),
+            start: prefix as u64,
+            period: period as u64,
+            residues: cycle
+                .into_iter()
+                .map(|step| (step % period) as u64)
+                .collect(),
+        }
     }
-    loop {
-        u %= v;
-        if u == 0 {
-            return (a / v) * b;
+
+    fn contains(&self, step: u64) -> bool {
+        if step < self.start {
+            self.tail.binary_search(&step).is_ok()
+        } else {
+            self.residues.contains(&(step % self.period))
         }
-        v %= u;
-        if v == 0 {
-            return (a / u) * b;
+    }
+}
+
+/// Finds the first step at which every ghost is on an end node.
+fn first_common_hit(hits: &[Hits]) -> Result<Option<Int>, Overflow> {
+    let Some(latest) = hits.iter().max_by_key(|hits| hits.start) else {
+        return Ok(None);
+    };
+    // Any earlier step lies in the tail of the ghost whose cycle starts last.
+    if let Some(&step) = latest
+        .tail
+        .iter()
+        .find(|&&step| hits.iter().all(|hits| hits.contains(step)))
+    {
+        return Ok(Some(Int::from(step)));
+    }
+
+    let mut congruences = vec![(Int::zero(), Int::one())];
+    for hits in hits {
+        let mut combined = Vec::new();
+        for (congruence, &residue) in congruences.iter().cartesian_product(&hits.residues) {
+            if let Some(congruence) = crt(congruence, (residue, hits.period))? {
+                combined.push(congruence);
+            }
         }
+        congruences = combined.into_iter().unique().collect();
+    }
+
+    let bound = Int::from(latest.start.max(1));
+    let steps = congruences
+        .into_iter()
+        .map(|(residue, modulus)| {
+            let Some(gap) = CheckedSub::checked_sub(&bound, &residue) else {
+                return Ok(residue);
+            };
+            let cycles = Integer::div_ceil(&gap, &modulus);
+            number::add(&residue, &number::mul(&modulus, &cycles)?)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(steps.into_iter().min())
+}
+
+/// Combines `x ≡ a (mod m)` and `x ≡ b (mod n)` for moduli that need not be coprime.
+fn crt((a, m): &(Int, Int), (b, n): (u64, u64)) -> Result<Option<(Int, Int)>, Overflow> {
+    let reduce = |value: &Int| value.mod_floor(&Int::from(n)).to_i128().unwrap();
+    let (g, p, _) = extended_gcd(reduce(m), n as i128);
+    let diff = b as i128 - reduce(a);
+    if diff % g != 0 {
+        return Ok(None);
+    }
+    let k = (diff / g * p).rem_euclid(n as i128 / g) as u64;
+    let x = number::add(a, &number::mul(m, &Int::from(k))?)?;
+    let modulus = number::mul(m, &Int::from(n / g as u64))?;
+    Ok(Some((x, modulus)))
+}
+
+/// Returns `(g, x, y)` with `g = gcd(a, b) = a * x + b * y`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - a / b * y)
     }
 }
 
+/// Counts the steps until the first end node, or `None` once the walk starts
+/// repeating without having reached one.
+fn get_steps(network: &Network, start: usize, is_end: impl Fn(usize) -> bool) -> Option<u64> {
+    let history = walk(network, start);
+    let steps = (1..=history.states.len()).find(|&n| is_end(history.state_at(n).0))?;
+    Some(steps as u64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
 
+    fn edges(network: &Network) -> Vec<(&str, &str, &str)> {
+        (0..network.len())
+            .map(|node| {
+                let [left, right] = network.edges[node];
+                (network.name(node), network.name(left), network.name(right))
+            })
+            .collect()
+    }
+
     #[test]
     fn parse_example() {
         let input = indoc! {"
@@ -105,21 +415,21 @@ mod tests {
             ZZZ = (ZZZ, ZZZ)
         "};
 
-        let result = (
-            vec!['R', 'L'],
-            HashMap::from([
-                ("AAA".into(), ("BBB".into(), "CCC".into())),
-                ("BBB".into(), ("DDD".into(), "EEE".into())),
-                ("CCC".into(), ("ZZZ".into(), "GGG".into())),
-                ("DDD".into(), ("DDD".into(), "DDD".into())),
-                ("EEE".into(), ("EEE".into(), "EEE".into())),
-                ("GGG".into(), ("GGG".into(), "GGG".into())),
-                ("ZZZ".into(), ("ZZZ".into(), "ZZZ".into())),
-            ]),
+        let network = parse(input).unwrap();
+        assert_eq!(network.directions, vec!['R', 'L']);
+        assert_eq!(
+            edges(&network),
+            vec![
+                ("AAA", "BBB", "CCC"),
+                ("BBB", "DDD", "EEE"),
+                ("CCC", "ZZZ", "GGG"),
+                ("DDD", "DDD", "DDD"),
+                ("EEE", "EEE", "EEE"),
+                ("GGG", "GGG", "GGG"),
+                ("ZZZ", "ZZZ", "ZZZ"),
+            ]
         );
 
-        assert_eq!(parse(input), result);
-
         let input = indoc! {"
             LLR
 
@@ -128,17 +438,17 @@ mod tests {
             ZZZ = (ZZZ, ZZZ)
         "};
 
-        let result = (
-            vec!['L', 'L', 'R'],
-            HashMap::from([
-                ("AAA".into(), ("BBB".into(), "BBB".into())),
-                ("BBB".into(), ("AAA".into(), "ZZZ".into())),
-                ("ZZZ".into(), ("ZZZ".into(), "ZZZ".into())),
-            ]),
+        let network = parse(input).unwrap();
+        assert_eq!(network.directions, vec!['L', 'L', 'R']);
+        assert_eq!(
+            edges(&network),
+            vec![
+                ("AAA", "BBB", "BBB"),
+                ("BBB", "AAA", "ZZZ"),
+                ("ZZZ", "ZZZ", "ZZZ"),
+            ]
         );
 
-        assert_eq!(parse(input), result);
-
         let input = indoc! {"
             LR
 
@@ -152,21 +462,48 @@ mod tests {
             XXX = (XXX, XXX)
         "};
 
-        let result = (
-            vec!['L', 'R'],
-            HashMap::from([
-                ("11A".into(), ("11B".into(), "XXX".into())),
-                ("11B".into(), ("XXX".into(), "11Z".into())),
-                ("11Z".into(), ("11B".into(), "XXX".into())),
-                ("22A".into(), ("22B".into(), "XXX".into())),
-                ("22B".into(), ("22C".into(), "22C".into())),
-                ("22C".into(), ("22Z".into(), "22Z".into())),
-                ("22Z".into(), ("22B".into(), "22B".into())),
-                ("XXX".into(), ("XXX".into(), "XXX".into())),
-            ]),
+        let network = parse(input).unwrap();
+        assert_eq!(network.directions, vec!['L', 'R']);
+        assert_eq!(
+            edges(&network),
+            vec![
+                ("11A", "11B", "XXX"),
+                ("11B", "XXX", "11Z"),
+                ("11Z", "11B", "XXX"),
+                ("22A", "22B", "XXX"),
+                ("22B", "22C", "22C"),
+                ("22C", "22Z", "22Z"),
+                ("22Z", "22B", "22B"),
+                ("XXX", "XXX", "XXX"),
+            ]
         );
+    }
+
+    #[test]
+    fn parse_error() {
+        let input = indoc! {"
+            LR
 
-        assert_eq!(parse(input), result);
+            AAA = (BBB, ZZZ)
+            ZZZ = (ZZZ, ZZZ)
+        "};
+
+        assert_eq!(
+            parse(input).unwrap_err().to_string(),
+            "day 8, line 3, column 8: unknown node (at \"BBB\")"
+        );
+
+        let input = indoc! {"
+            LR
+
+            AAA = (AAA, AAA)
+            AAA = (AAA, AAA)
+        "};
+
+        assert_eq!(
+            parse(input).unwrap_err().to_string(),
+            "day 8, line 4, column 1: duplicate node (at \"AAA\")"
+        );
     }
 
     #[test]
@@ -183,7 +520,7 @@ mod tests {
             ZZZ = (ZZZ, ZZZ)
         "};
 
-        assert_eq!(part1(&parse(input)), 2);
+        assert_eq!(part1(&parse(input).unwrap()), Ok(2));
 
         let input = indoc! {"
             LLR
@@ -193,7 +530,22 @@ mod tests {
             ZZZ = (ZZZ, ZZZ)
         "};
 
-        assert_eq!(part1(&parse(input)), 6);
+        assert_eq!(part1(&parse(input).unwrap()), Ok(6));
+
+        let input = input.replace("ZZZ", "YYY");
+        assert_eq!(
+            part1(&parse(&input).unwrap()).unwrap_err().to_string(),
+            "no node named ZZZ"
+        );
+
+        let input = indoc! {"
+            LR
+
+            AAA = (BBB, BBB)
+            BBB = (AAA, AAA)
+            ZZZ = (ZZZ, ZZZ)
+        "};
+        assert_eq!(part1(&parse(input).unwrap()), Err(StepsError::Unreachable));
     }
 
     #[test]
@@ -211,6 +563,131 @@ mod tests {
             XXX = (XXX, XXX)
         "};
 
-        assert_eq!(part2(&parse(input)), 6);
+        assert_eq!(part2(&parse(input).unwrap()), Ok(Int::from(6u64)));
+    }
+
+    #[test]
+    fn part2_offset_cycles() {
+        // 1A hits Z at steps 2, 5, 8, ... and 2A at steps 1, 3, 5, ...
+        let input = indoc! {"
+            L
+
+            1A = (1B, 1B)
+            1B = (1Z, 1Z)
+            1Z = (1C, 1C)
+            1C = (1B, 1B)
+            2A = (2Z, 2Z)
+            2Z = (2B, 2B)
+            2B = (2Z, 2Z)
+        "};
+        let network = parse(input).unwrap();
+
+        assert_eq!(part2(&network), Ok(Int::from(5u64)));
+        assert_eq!(
+            Hits::new(
+                &walk(&network, 0),
+                &[false, false, true, false, false, true, false]
+            ),
+            Hits {
+                tail: vec![],
+                start: 1,
+                period: 3,
+                residues: vec![2],
+            }
+        );
+
+        // 3A hits Z at steps 2, 4, 6, ..., never together with 2A.
+        let input = format!("{input}3A = (3B, 3B)\n3B = (3Z, 3Z)\n3Z = (3B, 3B)\n");
+        assert_eq!(part2(&parse(&input).unwrap()), Err(GhostError::Never));
+
+        // 4A only hits Z once, before its cycle starts.
+        let input = indoc! {"
+            LR
+
+            2A = (2Z, 2Z)
+            2Z = (2B, 2B)
+            2B = (2Z, 2Z)
+            4A = (4Z, XXX)
+            4Z = (XXX, XXX)
+            XXX = (XXX, XXX)
+        "};
+        assert_eq!(part2(&parse(input).unwrap()), Ok(Int::from(1u64)));
+
+        let input = input.replace('A', "Y");
+        assert_eq!(part2(&parse(&input).unwrap()), Err(GhostError::NoGhosts));
+    }
+
+    #[test]
+    fn crt() {
+        let crt = |(a, m): (u64, u64), other| {
+            super::crt(&(Int::from(a), Int::from(m)), other)
+                .unwrap()
+                .map(|(x, modulus)| (x.to_u64().unwrap(), modulus.to_u64().unwrap()))
+        };
+
+        assert_eq!(crt((2, 3), (1, 2)), Some((5, 6)));
+        assert_eq!(crt((2, 4), (4, 6)), Some((10, 12)));
+        assert_eq!(crt((1, 4), (2, 6)), None);
+        assert_eq!(crt((0, 1), (3, 7)), Some((3, 7)));
+        assert_eq!(crt((3, 7), (0, 7)), None);
+    }
+
+    #[test]
+    fn crt_overflow() {
+        let primes = [4294967291, 4294967279, 4294967231];
+        let result = primes
+            .iter()
+            .try_fold((Int::zero(), Int::one()), |acc, &p| {
+                super::crt(&acc, (1, p)).map(Option::unwrap)
+            });
+
+        #[cfg(not(feature = "bigint"))]
+        assert_eq!(result, Err(Overflow));
+        #[cfg(feature = "bigint")]
+        assert_eq!(
+            result.unwrap().1.to_string(),
+            (Int::from(4294967291u64) * 4294967279u64 * 4294967231u64).to_string()
+        );
+    }
+
+    #[test]
+    fn analysis() {
+        let input = indoc! {"
+            LR
+
+            11A = (11B, XXX)
+            11B = (XXX, 11Z)
+            11Z = (11B, XXX)
+            22A = (22B, XXX)
+            22B = (22C, 22C)
+            22C = (22Z, 22Z)
+            22Z = (22B, 22B)
+            XXX = (XXX, XXX)
+        "};
+        let analysis = Analysis::new(&parse(input).unwrap());
+
+        assert_eq!(
+            analysis.ghosts[0],
+            Ghost {
+                start: "11A".into(),
+                reachable: vec!["11A".into(), "11B".into(), "11Z".into(), "XXX".into()],
+                tail: 1,
+                cycle: 2,
+                cycle_ends: vec!["11Z".into()],
+                hits: Hits {
+                    tail: vec![],
+                    start: 1,
+                    period: 2,
+                    residues: vec![0],
+                },
+            }
+        );
+        assert_eq!(
+            analysis.to_string(),
+            indoc! {"
+                11A: 4 reachable, tail 1, cycle 2, ends on cycle [11Z]
+                22A: 5 reachable, tail 1, cycle 6, ends on cycle [22Z]
+                dead: [XXX]"}
+        );
     }
 }
@@ -1,50 +1,195 @@
+use crate::parse::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter, Write};
 
 #[aoc_generator(day9)]
-fn parse(input: &str) -> Vec<Vec<i32>> {
+fn parse(input: &str) -> Result<Vec<Vec<i128>>, ParseError> {
+    let source = Source::new(9, input);
     input
         .lines()
         .map(|line| {
             line.split_whitespace()
-                .map(str::parse)
-                .map(Result::unwrap)
+                .map(|value| source.parse(value))
                 .collect()
         })
         .collect()
 }
 
 #[aoc(day9, part1)]
-fn part1(input: &[Vec<i32>]) -> i32 {
-    input.iter().map(|seq| extrapolate(seq, false)).sum()
+fn part1(input: &[Vec<i128>]) -> Result<i128, SequenceError> {
+    sum(input, |seq| Polynomial::fit(seq)?.at(seq.len() as i128))
 }
 
 #[aoc(day9, part2)]
-fn part2(input: &[Vec<i32>]) -> i32 {
-    input.iter().map(|seq| extrapolate(seq, true)).sum()
+fn part2(input: &[Vec<i128>]) -> Result<i128, SequenceError> {
+    sum(input, |seq| Polynomial::fit(seq)?.at(-1))
 }
 
-fn extrapolate(sequence: &[i32], backwards: bool) -> i32 {
-    let mut iter = vec![sequence.to_vec()];
-    while !iter.last().unwrap().iter().all_equal() {
-        iter.push(
-            iter.last()
-                .unwrap()
+fn sum(
+    input: &[Vec<i128>],
+    predict: impl Fn(&[i128]) -> Result<i128, SequenceError>,
+) -> Result<i128, SequenceError> {
+    input.iter().try_fold(0i128, |total, seq| {
+        total
+            .checked_add(predict(seq)?)
+            .ok_or(SequenceError::Overflow)
+    })
+}
+
+/// Extrapolates a sequence to index `k`, which may lie before or after it,
+/// returning the value and the degree of the polynomial through the sequence.
+pub fn extrapolate(sequence: &[i128], k: i128) -> Result<(i128, usize), SequenceError> {
+    extrapolate_confirmed(sequence, k, 1)
+}
+
+/// Like [`extrapolate`], but trusts the polynomial only when its first constant
+/// row of differences is followed by at least `required` further rows.
+pub fn extrapolate_confirmed(
+    sequence: &[i128],
+    k: i128,
+    required: usize,
+) -> Result<(i128, usize), SequenceError> {
+    let polynomial = Polynomial::fit_confirmed(sequence, required)?;
+    Ok((polynomial.at(k)?, polynomial.degree()))
+}
+
+/// Prints the polynomial through each line of a day 9 input and its value at
+/// index `at`, or at the index after the line when `at` is `None`, requiring
+/// `required` confirmations as in [`extrapolate_confirmed`].
+pub fn extrapolate_sequences(
+    input: &str,
+    at: Option<i128>,
+    required: usize,
+) -> Result<String, Box<dyn Error>> {
+    let mut output = String::new();
+    for (n, sequence) in parse(input)?.iter().enumerate() {
+        let line = |e: SequenceError| format!("line {}: {e}", n + 1);
+        let polynomial = Polynomial::fit_confirmed(sequence, required).map_err(line)?;
+        let k = at.unwrap_or(sequence.len() as i128);
+        let value = polynomial.at(k).map_err(line)?;
+        writeln!(output, "f({k}) = {value}, {polynomial}")?;
+    }
+    Ok(output)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SequenceError {
+    Empty,
+    /// The differences only became constant in the last row, which has a single value.
+    NotPolynomial,
+    /// The differences became constant in a row with too few values to trust.
+    Unconfirmed {
+        degree: usize,
+        confirmations: usize,
+        required: usize,
+    },
+    Overflow,
+}
+
+impl Display for SequenceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SequenceError::Empty => write!(f, "empty sequence"),
+            SequenceError::NotPolynomial => write!(
+                f,
+                "not a polynomial of degree below the sequence length minus one"
+            ),
+            SequenceError::Unconfirmed {
+                degree,
+                confirmations,
+                required,
+            } => write!(
+                f,
+                "degree {degree} is confirmed by {confirmations} rows of constant differences, \
+                 {required} required"
+            ),
+            SequenceError::Overflow => write!(f, "value overflows i128"),
+        }
+    }
+}
+
+impl Error for SequenceError {}
+
+/// A sequence as a polynomial in its index, stored as the leading entries of its difference table.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Polynomial {
+    differences: Vec<i128>,
+}
+
+impl Polynomial {
+    fn fit(sequence: &[i128]) -> Result<Self, SequenceError> {
+        Self::fit_confirmed(sequence, 1)
+    }
+
+    /// Fits the sequence, requiring the first constant row of differences to be
+    /// followed by at least `required` further rows, so that it has `required + 1` values.
+    fn fit_confirmed(sequence: &[i128], required: usize) -> Result<Self, SequenceError> {
+        let mut row = sequence.to_vec();
+        let mut differences = vec![*row.first().ok_or(SequenceError::Empty)?];
+        while !row.iter().all_equal() {
+            row = row
                 .iter()
                 .tuple_windows()
-                .map(|(a, b)| b - a)
-                .collect(),
-        );
+                .map(|(a, b)| b.checked_sub(*a).ok_or(SequenceError::Overflow))
+                .collect::<Result<_, _>>()?;
+            differences.push(row[0]);
+        }
+
+        let confirmations = row.len() - 1;
+        if confirmations == 0 {
+            return Err(SequenceError::NotPolynomial);
+        }
+        if confirmations < required {
+            return Err(SequenceError::Unconfirmed {
+                degree: differences.len() - 1,
+                confirmations,
+                required,
+            });
+        }
+        Ok(Self { differences })
+    }
+
+    fn degree(&self) -> usize {
+        self.differences.len() - 1
     }
-    let mut current_value = 0;
-    for a in iter.iter().rev() {
-        if backwards {
-            current_value = a.first().unwrap() - current_value;
-        } else {
-            current_value += a.last().unwrap();
+
+    /// The value at index `k`, which may lie before or after the sequence.
+    /// Uses Newton's forward difference formula, `f(k) = sum of C(k, j) * Δʲf(0)`.
+    fn at(&self, k: i128) -> Result<i128, SequenceError> {
+        self.try_at(k).ok_or(SequenceError::Overflow)
+    }
+
+    fn try_at(&self, k: i128) -> Option<i128> {
+        let mut binomial = 1i128;
+        let mut value = 0i128;
+        for (j, &difference) in self.differences.iter().enumerate() {
+            if j > 0 {
+                // C(k, j) = C(k, j - 1) * (k - j + 1) / j, which always divides exactly.
+                binomial = binomial.checked_mul(k - j as i128 + 1)? / j as i128;
+            }
+            value = value.checked_add(binomial.checked_mul(difference)?)?;
         }
+        Some(value)
+    }
+}
+
+impl Display for Polynomial {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "degree {}: f(k) = {}",
+            self.degree(),
+            self.differences[0]
+        )?;
+        for (j, &difference) in self.differences.iter().enumerate().skip(1) {
+            if difference != 0 {
+                write!(f, " + {difference}·C(k, {j})")?;
+            }
+        }
+        Ok(())
     }
-    current_value
 }
 
 #[cfg(test)]
@@ -62,11 +207,11 @@ mod tests {
 
         assert_eq!(
             parse(input),
-            vec![
+            Ok(vec![
                 vec![0, 3, 6, 9, 12, 15],
                 vec![1, 3, 6, 10, 15, 21],
                 vec![10, 13, 16, 21, 30, 45],
-            ]
+            ])
         )
     }
 
@@ -78,7 +223,7 @@ mod tests {
             10 13 16 21 30 45
         "};
 
-        assert_eq!(part1(&parse(input)), 114);
+        assert_eq!(part1(&parse(input).unwrap()), Ok(114));
     }
 
     #[test]
@@ -87,6 +232,108 @@ mod tests {
             10 13 16 21 30 45
         "};
 
-        assert_eq!(part2(&parse(input)), 5);
+        assert_eq!(part2(&parse(input).unwrap()), Ok(5));
+    }
+
+    #[test]
+    fn not_polynomial() {
+        let fit = Polynomial::fit;
+
+        assert_eq!(fit(&[]), Err(SequenceError::Empty));
+        assert_eq!(fit(&[4]), Err(SequenceError::NotPolynomial));
+        assert_eq!(fit(&[1, 2, 4, 8, 16]), Err(SequenceError::NotPolynomial));
+        assert_eq!(fit(&[1, 1, 2, 3, 5, 8]), Err(SequenceError::NotPolynomial));
+        assert!(fit(&[1, 2, 4, 8, 16, 31]).is_ok());
+        assert_eq!(fit(&[i128::MIN, i128::MAX]), Err(SequenceError::Overflow));
+
+        let squares = [0, 1, 4, 9, 16, 25];
+        assert!(Polynomial::fit_confirmed(&squares, 3).is_ok());
+        assert_eq!(
+            Polynomial::fit_confirmed(&squares, 4),
+            Err(SequenceError::Unconfirmed {
+                degree: 2,
+                confirmations: 3,
+                required: 4,
+            })
+        );
+        assert_eq!(
+            part1(&[vec![1, 2, 4, 8]]).unwrap_err().to_string(),
+            "not a polynomial of degree below the sequence length minus one"
+        );
+    }
+
+    #[test]
+    fn polynomial() {
+        let fit = |sequence: &[i128]| Polynomial::fit(sequence).unwrap();
+
+        assert_eq!(fit(&[0, 3, 6, 9, 12, 15]).degree(), 1);
+        assert_eq!(fit(&[1, 3, 6, 10, 15, 21]).degree(), 2);
+        assert_eq!(fit(&[7, 7, 7]).degree(), 0);
+
+        let cubic = fit(&[10, 13, 16, 21, 30, 45]);
+        assert_eq!(cubic.degree(), 3);
+        assert_eq!(cubic.differences, vec![10, 3, 0, 2]);
+        assert_eq!(cubic.at(6), Ok(68));
+        assert_eq!(cubic.at(-1), Ok(5));
+        assert_eq!(cubic.at(2), Ok(16));
+        assert_eq!(
+            cubic.to_string(),
+            "degree 3: f(k) = 10 + 3·C(k, 1) + 2·C(k, 3)"
+        );
+
+        let squares = fit(&[0, 1, 4, 9]);
+        assert_eq!(squares.at(-7), Ok(49));
+        assert_eq!(
+            squares.at(1_000_000_000_000),
+            Ok(1_000_000_000_000_000_000_000_000)
+        );
+        assert_eq!(squares.at(i128::MAX / 2), Err(SequenceError::Overflow));
+    }
+
+    #[test]
+    fn extrapolate_example() {
+        assert_eq!(extrapolate(&[10, 13, 16, 21, 30, 45], 6), Ok((68, 3)));
+        assert_eq!(extrapolate(&[0, 1, 4, 9], -7), Ok((49, 2)));
+        assert_eq!(extrapolate(&[4], 1), Err(SequenceError::NotPolynomial));
+        assert_eq!(extrapolate_confirmed(&[0, 1, 4, 9, 16], 5, 2), Ok((25, 2)));
+        assert_eq!(
+            extrapolate_confirmed(&[0, 1, 4, 9, 16], 5, 3),
+            Err(SequenceError::Unconfirmed {
+                degree: 2,
+                confirmations: 2,
+                required: 3,
+            })
+        );
+
+        let input = indoc! {"
+            0 3 6 9 12 15
+            10 13 16 21 30 45
+        "};
+        assert_eq!(
+            extrapolate_sequences(input, None, 1).unwrap(),
+            indoc! {"
+                f(6) = 18, degree 1: f(k) = 0 + 3·C(k, 1)
+                f(6) = 68, degree 3: f(k) = 10 + 3·C(k, 1) + 2·C(k, 3)
+            "}
+        );
+        assert_eq!(
+            extrapolate_sequences(input, Some(-1), 1).unwrap(),
+            indoc! {"
+                f(-1) = -3, degree 1: f(k) = 0 + 3·C(k, 1)
+                f(-1) = 5, degree 3: f(k) = 10 + 3·C(k, 1) + 2·C(k, 3)
+            "}
+        );
+        assert_eq!(
+            extrapolate_sequences("1 2 3\n1 2 4 8\n", None, 1)
+                .unwrap_err()
+                .to_string(),
+            "line 2: not a polynomial of degree below the sequence length minus one"
+        );
+        assert_eq!(
+            extrapolate_sequences(input, None, 5)
+                .unwrap_err()
+                .to_string(),
+            "line 1: degree 1 is confirmed by 4 rows of constant differences, 5 required"
+        );
     }
 }
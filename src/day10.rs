@@ -1,9 +1,10 @@
+use crate::grid::{parse_grid, Dir, Grid, GridExt, Pos};
+use crate::parse::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
-use grid::Grid;
 use itertools::Itertools;
-use std::cmp::max;
 use std::collections::VecDeque;
-use std::fmt::Debug;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter, Write};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Tiles {
@@ -19,24 +20,76 @@ enum Tiles {
 }
 
 impl Tiles {
-    fn new_facing(&self, facing: Facing) -> Facing {
+    fn new_facing(&self, facing: Dir) -> Dir {
         match self {
-            Tiles::NorthEast if facing == Facing::South => Facing::East,
-            Tiles::NorthEast if facing == Facing::West => Facing::North,
-            Tiles::NorthWest if facing == Facing::South => Facing::West,
-            Tiles::NorthWest if facing == Facing::East => Facing::North,
-            Tiles::SouthWest if facing == Facing::North => Facing::West,
-            Tiles::SouthWest if facing == Facing::East => Facing::South,
-            Tiles::SouthEast if facing == Facing::North => Facing::East,
-            Tiles::SouthEast if facing == Facing::West => Facing::South,
+            Tiles::NorthEast if facing == Dir::South => Dir::East,
+            Tiles::NorthEast if facing == Dir::West => Dir::North,
+            Tiles::NorthWest if facing == Dir::South => Dir::West,
+            Tiles::NorthWest if facing == Dir::East => Dir::North,
+            Tiles::SouthWest if facing == Dir::North => Dir::West,
+            Tiles::SouthWest if facing == Dir::East => Dir::South,
+            Tiles::SouthEast if facing == Dir::North => Dir::East,
+            Tiles::SouthEast if facing == Dir::West => Dir::South,
             _ => facing,
         }
     }
+
+    /// The two directions a pipe connects, `None` for ground and the start.
+    fn connections(self) -> Option<[Dir; 2]> {
+        Some(match self {
+            Tiles::NorthSouth => [Dir::North, Dir::South],
+            Tiles::EastWest => [Dir::East, Dir::West],
+            Tiles::NorthEast => [Dir::North, Dir::East],
+            Tiles::NorthWest => [Dir::North, Dir::West],
+            Tiles::SouthWest => [Dir::South, Dir::West],
+            Tiles::SouthEast => [Dir::South, Dir::East],
+            _ => return None,
+        })
+    }
+
+    fn connects(self, dir: Dir) -> bool {
+        self.connections()
+            .is_some_and(|connections| connections.contains(&dir))
+    }
+
+    /// The pipe connecting two different directions.
+    fn connecting(a: Dir, b: Dir) -> Tiles {
+        [
+            Tiles::NorthSouth,
+            Tiles::EastWest,
+            Tiles::NorthEast,
+            Tiles::NorthWest,
+            Tiles::SouthWest,
+            Tiles::SouthEast,
+        ]
+        .into_iter()
+        .find(|tile| {
+            let connections = tile.connections().unwrap();
+            connections.contains(&a) && connections.contains(&b)
+        })
+        .unwrap()
+    }
+
+    fn glyph(self) -> char {
+        match self {
+            Tiles::NorthSouth => '│',
+            Tiles::EastWest => '─',
+            Tiles::NorthEast => '└',
+            Tiles::NorthWest => '┘',
+            Tiles::SouthWest => '┐',
+            Tiles::SouthEast => '┌',
+            Tiles::Ground => '.',
+            Tiles::Start => 'S',
+            Tiles::Fake => ' ',
+        }
+    }
 }
 
-impl From<char> for Tiles {
-    fn from(value: char) -> Self {
-        match value {
+impl TryFrom<char> for Tiles {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Ok(match value {
             '|' => Tiles::NorthSouth,
             '-' => Tiles::EastWest,
             'L' => Tiles::NorthEast,
@@ -45,101 +98,137 @@ impl From<char> for Tiles {
             'F' => Tiles::SouthEast,
             '.' => Tiles::Ground,
             'S' => Tiles::Start,
-            _ => panic!("Unknown Tile"),
-        }
+            _ => return Err(format!("unknown tile {value:?}")),
+        })
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Facing {
-    North,
-    East,
-    South,
-    West,
+#[derive(Debug, PartialEq, Eq)]
+enum MazeError {
+    Missing,
+    Disconnected((usize, usize)),
+    Ambiguous((usize, usize), Vec<Tiles>),
+    OffMap((usize, usize)),
+    Broken((usize, usize), (usize, usize)),
 }
 
-impl Facing {
-    fn step(&self, location: (usize, usize)) -> (usize, usize) {
-        match self {
-            Facing::North => (location.0 - 1, location.1),
-            Facing::East => (location.0, location.1 + 1),
-            Facing::South => (location.0 + 1, location.1),
-            Facing::West => (location.0, location.1 - 1),
-        }
-    }
-
-    fn valid_tile(&self, tile: Tiles) -> bool {
+impl Display for MazeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            Facing::North => {
-                tile == Tiles::NorthSouth || tile == Tiles::SouthWest || tile == Tiles::SouthEast
-            }
-            Facing::East => {
-                tile == Tiles::EastWest || tile == Tiles::NorthWest || tile == Tiles::SouthWest
-            }
-            Facing::South => {
-                tile == Tiles::NorthSouth || tile == Tiles::NorthEast || tile == Tiles::NorthWest
-            }
-            Facing::West => {
-                tile == Tiles::EastWest || tile == Tiles::NorthEast || tile == Tiles::SouthEast
+            MazeError::Missing => write!(f, "no start tile"),
+            MazeError::Disconnected(pos) => {
+                write!(f, "start at {pos:?} connects to fewer than two pipes")
             }
+            MazeError::Ambiguous(pos, candidates) => write!(
+                f,
+                "start at {pos:?} could be any of {}",
+                candidates.iter().map(|tile| tile.glyph()).join(" ")
+            ),
+            MazeError::OffMap(pos) => write!(f, "pipe leads off the map at {pos:?}"),
+            MazeError::Broken(from, to) => write!(
+                f,
+                "pipe at {from:?} leads to {to:?}, which does not connect back"
+            ),
         }
     }
 }
 
+impl Error for MazeError {}
+
 #[aoc_generator(day10)]
-fn parse(input: &str) -> Grid<Tiles> {
-    let mut size = 0;
-    Grid::from_vec(
-        input
-            .lines()
-            .flat_map(|line| {
-                size = line.len();
-                line.chars().map(Tiles::from)
-            })
-            .collect(),
-        size,
-    )
+fn parse(input: &str) -> Result<Grid<Tiles>, ParseError> {
+    parse_grid(&Source::new(10, input), input)
 }
 
 #[aoc(day10, part1)]
-fn part1(input: &Grid<Tiles>) -> u32 {
-    let (start, _) = input
-        .indexed_iter()
-        .find(|(_, tile)| **tile == Tiles::Start)
-        .unwrap();
-    let (left, right) = get_valid_directions(input, start);
-
-    let mut left = (left, left.step(start), 1);
-    let mut right = (right, right.step(start), 1);
-    while left.1 != right.1 {
-        left.0 = input[left.1].new_facing(left.0);
-        left.1 = left.0.step(left.1);
-        left.2 += 1;
-
-        right.0 = input[right.1].new_facing(right.0);
-        right.1 = right.0.step(right.1);
-        right.2 += 1;
-    }
-
-    max(left.2, right.2)
+fn part1(input: &Grid<Tiles>) -> Result<usize, MazeError> {
+    let (_, main_loop) = with_loop(input)?;
+    Ok(main_loop.len() / 2)
 }
 
 #[aoc(day10, part2)]
-fn part2(input: &Grid<Tiles>) -> u32 {
+fn part2(input: &Grid<Tiles>) -> Result<u32, MazeError> {
+    let (input, main_loop) = with_loop(input)?;
+    Ok(flood_fill(&input, &main_loop))
+}
+
+#[aoc(day10, part2, Shoelace)]
+fn part2_shoelace(input: &Grid<Tiles>) -> Result<u32, MazeError> {
+    let (_, main_loop) = with_loop(input)?;
+    Ok(shoelace(&main_loop))
+}
+
+#[aoc(day10, part2, Scanline)]
+fn part2_scanline(input: &Grid<Tiles>) -> Result<u32, MazeError> {
+    let (input, main_loop) = with_loop(input)?;
+    Ok(scanline(&input, &main_loop))
+}
+
+/// Replaces the start with the only pipe connecting two of its neighbours.
+fn substitute_start(input: &Grid<Tiles>) -> Result<((usize, usize), Grid<Tiles>), MazeError> {
     let (start, _) = input
         .indexed_iter()
         .find(|(_, tile)| **tile == Tiles::Start)
-        .unwrap();
-    let mut facing = get_valid_directions(input, start).0;
+        .ok_or(MazeError::Missing)?;
+    let candidates = get_valid_directions(input, start)
+        .into_iter()
+        .tuple_combinations()
+        .map(|(a, b)| Tiles::connecting(a, b))
+        .collect::<Vec<_>>();
+
+    let mut grid = input.clone();
+    grid[start] = match candidates[..] {
+        [] => return Err(MazeError::Disconnected(start)),
+        [tile] => tile,
+        _ => return Err(MazeError::Ambiguous(start, candidates)),
+    };
+    Ok((start, grid))
+}
+
+type Loop = Vec<(usize, usize)>;
 
-    let mut main_loop = vec![start, facing.step(start)];
-    while main_loop.first().unwrap() != main_loop.last().unwrap() {
-        let pos = *main_loop.last().unwrap();
-        facing = input[pos].new_facing(facing);
-        main_loop.push(facing.step(pos));
+/// The grid with the start substituted, and the loop through the start.
+fn with_loop(input: &Grid<Tiles>) -> Result<(Grid<Tiles>, Loop), MazeError> {
+    let (start, input) = substitute_start(input)?;
+    let main_loop = main_loop(&input, start)?;
+    Ok((input, main_loop))
+}
+
+/// Returns the tiles of the loop through the start, in the order they are walked.
+fn main_loop(input: &Grid<Tiles>, start: (usize, usize)) -> Result<Loop, MazeError> {
+    let mut main_loop = Vec::new();
+    let facing = input[start].connections().unwrap()[0];
+    trace(input, start, facing, &mut main_loop).map(|()| main_loop)
+}
+
+/// Walks the pipes from `start` towards `facing`, pushing every tile it passes onto `path` until
+/// it returns to `start` or runs into a tile that doesn't connect back.
+fn trace(
+    input: &Grid<Tiles>,
+    start: (usize, usize),
+    mut facing: Dir,
+    path: &mut Loop,
+) -> Result<(), MazeError> {
+    path.push(start);
+    loop {
+        let pos = *path.last().unwrap();
+        let next: (usize, usize) = input
+            .step(pos.into(), facing)
+            .ok_or(MazeError::OffMap(pos))?
+            .into();
+        if !input[next].connects(facing.opposite()) {
+            return Err(MazeError::Broken(pos, next));
+        }
+        if next == start {
+            return Ok(());
+        }
+        facing = input[next].new_facing(facing);
+        path.push(next);
     }
-    main_loop.pop();
+}
 
+/// Counts the enclosed tiles by flooding the outside of a grid at double resolution.
+fn flood_fill(input: &Grid<Tiles>, main_loop: &[(usize, usize)]) -> u32 {
     let mut grid = Grid::init(input.rows() * 2 + 2, input.cols() * 2 + 2, Tiles::Fake);
     input
         .indexed_iter()
@@ -177,62 +266,314 @@ fn part2(input: &Grid<Tiles>) -> u32 {
                     grid[(new.0 + 1, new.1)] = Tiles::NorthSouth;
                     grid[(new.0, new.1 + 1)] = Tiles::EastWest;
                 }
-                Tiles::Start => {
-                    if old.0 > 0 && main_loop.contains(&(old.0 - 1, old.1)) {
-                        grid[(new.0 - 1, new.1)] = Tiles::NorthSouth;
-                    }
-                    if old.0 < input.rows() - 1 && main_loop.contains(&(old.0 + 1, old.1)) {
-                        grid[(new.0 + 1, new.1)] = Tiles::NorthSouth;
-                    }
-                    if old.1 > 0 && main_loop.contains(&(old.0, old.1 - 1)) {
-                        grid[(new.0, new.1 - 1)] = Tiles::EastWest;
-                    }
-                    if old.1 < input.cols() - 1 && main_loop.contains(&(old.0, old.1 + 1)) {
-                        grid[(new.0, new.1 + 1)] = Tiles::EastWest;
-                    }
-                }
                 _ => {}
             }
         });
 
     let mut queue = VecDeque::new();
-    queue.push_back((0, 0));
-    while !queue.is_empty() {
-        let node = queue.pop_front().unwrap();
+    queue.push_back(Pos::new(0, 0));
+    while let Some(node) = queue.pop_front() {
         if grid[node] == Tiles::Ground || grid[node] == Tiles::Fake {
             grid[node] = Tiles::Start;
-            if node.0 > 0 {
-                queue.push_back((node.0 - 1, node.1));
+            queue.extend(grid.neighbours4(node).map(|(_, pos)| pos));
+        }
+    }
+
+    grid.iter().filter(|tile| **tile == Tiles::Ground).count() as u32
+}
+
+/// Counts the enclosed tiles from the loop's area with the shoelace formula and Pick's theorem:
+/// `area = interior + boundary / 2 - 1`.
+fn shoelace(main_loop: &[(usize, usize)]) -> u32 {
+    let twice_area = main_loop
+        .iter()
+        .circular_tuple_windows()
+        .map(|(&(r1, c1), &(r2, c2))| (r1 * c2) as i64 - (r2 * c1) as i64)
+        .sum::<i64>()
+        .unsigned_abs();
+    ((twice_area + 2 - main_loop.len() as u64) / 2) as u32
+}
+
+/// Counts the enclosed tiles row by row, toggling between outside and inside
+/// on every loop tile that connects north.
+fn scanline(input: &Grid<Tiles>, main_loop: &[(usize, usize)]) -> u32 {
+    enclosed(input, main_loop)
+        .iter()
+        .filter(|&&inside| inside)
+        .count() as u32
+}
+
+fn on_loop(input: &Grid<Tiles>, main_loop: &[(usize, usize)]) -> Grid<bool> {
+    let mut on_loop = Grid::init(input.rows(), input.cols(), false);
+    for &pos in main_loop {
+        on_loop[pos] = true;
+    }
+    on_loop
+}
+
+/// Marks the tiles off the loop that it encloses.
+fn enclosed(input: &Grid<Tiles>, main_loop: &[(usize, usize)]) -> Grid<bool> {
+    let on_loop = on_loop(input, main_loop);
+
+    let mut enclosed = Grid::init(input.rows(), input.cols(), false);
+    for row in 0..input.rows() {
+        let mut inside = false;
+        for col in 0..input.cols() {
+            if !on_loop[(row, col)] {
+                enclosed[(row, col)] = inside;
+                continue;
             }
-            if node.0 < grid.rows() - 1 {
-                queue.push_back((node.0 + 1, node.1));
+            inside ^= matches!(
+                input[(row, col)],
+                Tiles::NorthSouth | Tiles::NorthEast | Tiles::NorthWest
+            );
+        }
+    }
+    enclosed
+}
+
+/// Output formats for [`render`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Backend {
+    Plain,
+    Ansi,
+    Svg,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Cell {
+    Loop(Tiles),
+    Inside(Tiles),
+    Outside(Tiles),
+}
+
+fn cells(input: &Grid<Tiles>) -> Result<Grid<Cell>, MazeError> {
+    let (input, main_loop) = with_loop(input)?;
+    let on_loop = on_loop(&input, &main_loop);
+    let enclosed = enclosed(&input, &main_loop);
+
+    let mut cells = Grid::init(input.rows(), input.cols(), Cell::Outside(Tiles::Ground));
+    for (pos, &tile) in input.indexed_iter() {
+        cells[pos] = if on_loop[pos] {
+            Cell::Loop(tile)
+        } else if enclosed[pos] {
+            Cell::Inside(tile)
+        } else {
+            Cell::Outside(tile)
+        };
+    }
+    Ok(cells)
+}
+
+/// Draws the main loop of a maze in box-drawing characters, with the tiles it
+/// encloses marked `I` and the rest `O`. The ANSI and SVG backends also show
+/// the junk pipes off the loop, dimmed.
+pub fn render(input: &str, backend: Backend) -> Result<String, Box<dyn Error>> {
+    let cells = cells(&parse(input)?)?;
+    Ok(match backend {
+        Backend::Plain => render_text(&cells, |cell| match cell {
+            Cell::Loop(tile) => tile.glyph().to_string(),
+            Cell::Inside(_) => "I".to_string(),
+            Cell::Outside(_) => "O".to_string(),
+        }),
+        Backend::Ansi => render_text(&cells, |cell| match cell {
+            Cell::Loop(tile) => format!("\x1b[1m{}\x1b[0m", tile.glyph()),
+            Cell::Inside(Tiles::Ground) => "\x1b[32mI\x1b[0m".to_string(),
+            Cell::Outside(Tiles::Ground) => "\x1b[34mO\x1b[0m".to_string(),
+            Cell::Inside(tile) => format!("\x1b[2;32m{}\x1b[0m", tile.glyph()),
+            Cell::Outside(tile) => format!("\x1b[2;34m{}\x1b[0m", tile.glyph()),
+        }),
+        Backend::Svg => render_svg(&cells),
+    })
+}
+
+fn render_text(cells: &Grid<Cell>, draw: impl Fn(Cell) -> String) -> String {
+    let mut text = String::new();
+    for row in cells.iter_rows() {
+        text.extend(row.map(|&cell| draw(cell)));
+        text.push('\n');
+    }
+    text
+}
+
+fn render_svg(cells: &Grid<Cell>) -> String {
+    const SIZE: usize = 10;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+        cells.cols() * SIZE,
+        cells.rows() * SIZE
+    );
+    let mut main_loop = String::new();
+    let mut junk = String::new();
+    for ((row, col), &cell) in cells.indexed_iter() {
+        let (x, y) = (col * SIZE, row * SIZE);
+        let (fill, tile, path) = match cell {
+            Cell::Loop(tile) => ("white", tile, &mut main_loop),
+            Cell::Inside(tile) => ("#9d9", tile, &mut junk),
+            Cell::Outside(tile) => ("#ddf", tile, &mut junk),
+        };
+        writeln!(
+            svg,
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{SIZE}\" height=\"{SIZE}\" fill=\"{fill}\"/>"
+        )
+        .unwrap();
+        for facing in tile.connections().into_iter().flatten() {
+            let (dy, dx) = facing.delta();
+            let half = SIZE as isize / 2;
+            write!(
+                path,
+                "M{} {}l{} {}",
+                x as isize + half,
+                y as isize + half,
+                dx * half,
+                dy * half
+            )
+            .unwrap();
+        }
+    }
+    writeln!(
+        svg,
+        "<path d=\"{junk}\" stroke=\"#999\" stroke-width=\"1\"/>\n\
+         <path d=\"{main_loop}\" stroke=\"black\" stroke-width=\"3\" stroke-linecap=\"square\"/>\n\
+         </svg>"
+    )
+    .unwrap();
+    svg
+}
+
+/// Every closed pipe cycle in a maze, not just the one through the start.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Analysis {
+    pub cycles: Vec<Cycle>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The corner tiles as (row, column), in the order they are walked.
+    pub vertices: Vec<(usize, usize)>,
+    pub length: usize,
+    /// The tiles off the cycle that it encloses.
+    pub area: u32,
+    /// The index of the innermost cycle this one is nested in.
+    pub parent: Option<usize>,
+}
+
+impl Analysis {
+    fn new(input: &Grid<Tiles>) -> Self {
+        // A start that can't be resolved is left as a tile without pipes.
+        let input = match substitute_start(input) {
+            Ok((_, grid)) => grid,
+            Err(_) => input.clone(),
+        };
+
+        let mut owner = Grid::init(input.rows(), input.cols(), None);
+        let mut loops = Vec::new();
+        let mut seen = Grid::init(input.rows(), input.cols(), false);
+        for (pos, tile) in input.indexed_iter() {
+            if seen[pos] || tile.connections().is_none() {
+                continue;
             }
-            if node.1 > 0 {
-                queue.push_back((node.0, node.1 - 1));
+            // Every tile on a failed trace links back to `pos`, so none of them is on a cycle.
+            // Tracing the other way too covers the whole open chain, whichever tile it's found at.
+            let [forward, backward] = tile.connections().unwrap();
+            let mut path = Vec::new();
+            let traced = trace(&input, pos, forward, &mut path);
+            if traced.is_err() {
+                let _ = trace(&input, pos, backward, &mut path);
             }
-            if node.1 < grid.cols() - 1 {
-                queue.push_back((node.0, node.1 + 1));
+            for &pos in &path {
+                seen[pos] = true;
+            }
+            if traced.is_ok() {
+                for &pos in &path {
+                    owner[pos] = Some(loops.len());
+                }
+                loops.push(path);
             }
         }
+
+        let parents = parents(&input, &owner, loops.len());
+        let cycles = loops
+            .iter()
+            .zip(parents)
+            .map(|(main_loop, parent)| Cycle {
+                vertices: main_loop
+                    .iter()
+                    .copied()
+                    .filter(|&pos| !matches!(input[pos], Tiles::NorthSouth | Tiles::EastWest))
+                    .collect(),
+                length: main_loop.len(),
+                area: shoelace(main_loop),
+                parent,
+            })
+            .collect();
+        Self { cycles }
     }
+}
 
-    grid.iter().filter(|tile| **tile == Tiles::Ground).count() as u32
+/// Finds the innermost cycle around each of `count` cycles in a single scan.
+/// Cycles never cross, so the ones open at any point of a row form a stack:
+/// a cycle is closed by the next crossing of its own before any outer one.
+fn parents(input: &Grid<Tiles>, owner: &Grid<Option<usize>>, count: usize) -> Vec<Option<usize>> {
+    let mut parents = vec![None; count];
+    let mut found = vec![false; count];
+    for row in 0..input.rows() {
+        let mut open: Vec<usize> = Vec::new();
+        for col in 0..input.cols() {
+            let Some(cycle) = owner[(row, col)] else {
+                continue;
+            };
+            if !found[cycle] {
+                found[cycle] = true;
+                parents[cycle] = open.last().copied();
+            }
+            if input[(row, col)].connects(Dir::North) {
+                if open.last() == Some(&cycle) {
+                    open.pop();
+                } else {
+                    open.push(cycle);
+                }
+            }
+        }
+    }
+    parents
 }
 
-fn get_valid_directions(grid: &Grid<Tiles>, pos: (usize, usize)) -> (Facing, Facing) {
-    [Facing::North, Facing::East, Facing::South, Facing::West]
-        .iter()
-        .copied()
-        .filter(|facing| match facing {
-            Facing::North if pos.0 == 0 => false,
-            Facing::East if pos.1 == grid.size().1 => false,
-            Facing::South if pos.0 == grid.size().0 => false,
-            Facing::West if pos.1 == 0 => false,
-            _ => true,
+impl Display for Analysis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, cycle) in self.cycles.iter().enumerate() {
+            write!(
+                f,
+                "loop {i}: length {}, encloses {}, {} corners from {:?}",
+                cycle.length,
+                cycle.area,
+                cycle.vertices.len(),
+                cycle.vertices[0]
+            )?;
+            match cycle.parent {
+                Some(parent) => writeln!(f, ", inside loop {parent}")?,
+                None => writeln!(f, ", outermost")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Finds every closed pipe loop in a maze with its corners, length, the tiles
+/// it encloses and the loop it is nested in.
+pub fn analyse_loops(input: &str) -> Result<Analysis, Box<dyn Error>> {
+    Ok(Analysis::new(&parse(input)?))
+}
+
+fn get_valid_directions(grid: &Grid<Tiles>, pos: (usize, usize)) -> Vec<Dir> {
+    Dir::ALL
+        .into_iter()
+        .filter(|&dir| {
+            grid.step(pos.into(), dir)
+                .is_some_and(|next| grid[next].connects(dir.opposite()))
         })
-        .filter(|facing| facing.valid_tile(grid[facing.step(pos)]))
-        .collect_tuple()
-        .unwrap()
+        .collect()
 }
 
 #[cfg(test)]
@@ -261,7 +602,7 @@ mod tests {
             [NorthEast, NorthSouth, EastWest, NorthWest, SouthEast]
         ];
 
-        assert_eq!(parse(input), result);
+        assert_eq!(parse(input), Ok(result));
 
         let input = indoc! {"
             7-F7-
@@ -279,7 +620,31 @@ mod tests {
             [NorthEast, NorthWest, Ground, NorthEast, NorthWest]
         ];
 
-        assert_eq!(parse(input), result);
+        assert_eq!(parse(input), Ok(result));
+    }
+
+    #[test]
+    fn parse_error() {
+        let input = indoc! {"
+            .....
+            .S-7.
+            .|x|.
+        "};
+
+        assert_eq!(
+            parse(input).unwrap_err().to_string(),
+            "day 10, line 3, column 3: unknown tile 'x' (at \"x\")"
+        );
+
+        let input = indoc! {"
+            .....
+            .S-7
+        "};
+
+        assert_eq!(
+            parse(input).unwrap_err().to_string(),
+            "day 10, line 2, column 1: line length differs from the first line (at \".S-7\")"
+        );
     }
 
     #[test]
@@ -292,7 +657,7 @@ mod tests {
             .....
         "};
 
-        assert_eq!(part1(&parse(input)), 4);
+        assert_eq!(part1(&parse(input).unwrap()), Ok(4));
 
         let input = indoc! {"
             ..F7.
@@ -302,7 +667,7 @@ mod tests {
             LJ...
         "};
 
-        assert_eq!(part1(&parse(input)), 8);
+        assert_eq!(part1(&parse(input).unwrap()), Ok(8));
     }
 
     #[test]
@@ -319,7 +684,7 @@ mod tests {
             ...........
         "};
 
-        assert_eq!(part2(&parse(input)), 4);
+        assert_eq!(part2(&parse(input).unwrap()), Ok(4));
 
         let input = indoc! {"
             .F----7F7F7F7F-7....
@@ -334,7 +699,7 @@ mod tests {
             ....L---J.LJ.LJLJ...
         "};
 
-        assert_eq!(part2(&parse(input)), 8);
+        assert_eq!(part2(&parse(input).unwrap()), Ok(8));
 
         let input = indoc! {"
             FF7FSF7F7F7F7F7F---7
@@ -349,6 +714,286 @@ mod tests {
             L7JLJL-JLJLJL--JLJ.L
         "};
 
-        assert_eq!(part2(&parse(input)), 10);
+        assert_eq!(part2(&parse(input).unwrap()), Ok(10));
+    }
+
+    #[test]
+    fn enclosed_methods_agree() {
+        let inputs = [
+            indoc! {"
+                S7
+                LJ
+            "},
+            indoc! {"
+                .....
+                .S-7.
+                .|.|.
+                .L-J.
+                .....
+            "},
+            indoc! {"
+                ..F7.
+                .FJ|.
+                SJ.L7
+                |F--J
+                LJ...
+            "},
+            indoc! {"
+                ..........
+                .S------7.
+                .|F----7|.
+                .||....||.
+                .||....||.
+                .|L-7F-J|.
+                .|..||..|.
+                .L--JL--J.
+                ..........
+            "},
+            indoc! {"
+                .F----7F7F7F7F-7....
+                .|F--7||||||||FJ....
+                .||.FJ||||||||L7....
+                FJL7L7LJLJ||LJ.L-7..
+                L--J.L7...LJS7F-7L7.
+                ....F-J..F7FJ|L7L7L7
+                ....L7.F7||L7|.L7L7|
+                .....|FJLJ|FJ|F7|.LJ
+                ....FJL-7.||.||||...
+                ....L---J.LJ.LJLJ...
+            "},
+            indoc! {"
+                FF7FSF7F7F7F7F7F---7
+                L|LJ||||||||||||F--J
+                FL-7LJLJ||||||LJL-77
+                F--JF--7||LJLJ7F7FJ-
+                L---JF-JLJ.||-FJLJJ7
+                |F|F-JF---7F7-L7L|7|
+                |FFJF7L7F-JF7|JL---7
+                7-L-JL7||F7|L7F-7F7|
+                L.L7LFJ|||||FJL7||LJ
+                L7JLJL-JLJLJL--JLJ.L
+            "},
+        ];
+
+        for (input, expected) in inputs.into_iter().zip([0, 1, 1, 4, 8, 10]) {
+            let grid = parse(input).unwrap();
+            assert_eq!(part2(&grid), Ok(expected));
+            assert_eq!(part2_shoelace(&grid), Ok(expected));
+            assert_eq!(part2_scanline(&grid), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn render_example() {
+        let input = indoc! {"
+            ..........
+            .S------7.
+            .|F----7|.
+            .||.F7.||.
+            .||....||.
+            .|L-7F-J|.
+            .|..||..|.
+            .L--JL--J.
+            ..........
+        "};
+
+        let result = indoc! {"
+            OOOOOOOOOO
+            O┌──────┐O
+            O│┌────┐│O
+            O││OOOO││O
+            O││OOOO││O
+            O│└─┐┌─┘│O
+            O│II││II│O
+            O└──┘└──┘O
+            OOOOOOOOOO
+        "};
+
+        assert_eq!(render(input, Backend::Plain).unwrap(), result);
+
+        let ansi = render(input, Backend::Ansi).unwrap();
+        assert!(ansi.contains("\x1b[2;34m┌\x1b[0m\x1b[2;34m┐\x1b[0m"));
+        assert_eq!(ansi.matches("\x1b[32mI").count(), 4);
+
+        let svg = render(input, Backend::Svg).unwrap();
+        assert!(svg
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"90\">"));
+        assert_eq!(svg.matches("<rect").count(), 90);
+        assert_eq!(svg.matches("fill=\"#9d9\"").count(), 4);
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn substitute_start_example() {
+        let input = indoc! {"
+            7-F7-
+            .FJ|7
+            SJLL7
+            |F--J
+            LJ.LJ
+        "};
+
+        let (start, grid) = substitute_start(&parse(input).unwrap()).unwrap();
+        assert_eq!(start, (2, 0));
+        assert_eq!(grid[start], Tiles::SouthEast);
+
+        let input = indoc! {"
+            .|...
+            -S-7.
+            .|.|.
+            .L-J.
+        "};
+
+        assert_eq!(
+            substitute_start(&parse(input).unwrap())
+                .unwrap_err()
+                .to_string(),
+            "start at (1, 1) could be any of └ │ ┘ ┌ ─ ┐"
+        );
+
+        let input = indoc! {"
+            .....
+            .S-7.
+            ...|.
+            .L-J.
+        "};
+
+        assert_eq!(
+            part1(&parse(input).unwrap()),
+            Err(MazeError::Disconnected((1, 1)))
+        );
+        assert_eq!(part2(&parse("...\n").unwrap()), Err(MazeError::Missing));
+    }
+
+    #[test]
+    fn loop_on_every_edge() {
+        let inputs = [
+            indoc! {"
+                S-7
+                |.|
+                L-J
+            "},
+            indoc! {"
+                F-7
+                |.|
+                L-S
+            "},
+            indoc! {"
+                F7F-7
+                |LJ.S
+                L---J
+            "},
+        ];
+
+        for (input, expected) in inputs.into_iter().zip([(4, 1), (4, 1), (7, 1)]) {
+            let grid = parse(input).unwrap();
+            assert_eq!(part1(&grid), Ok(expected.0));
+            assert_eq!(part2(&grid), Ok(expected.1));
+            assert_eq!(part2_shoelace(&grid), Ok(expected.1));
+            assert_eq!(part2_scanline(&grid), Ok(expected.1));
+        }
+    }
+
+    #[test]
+    fn loop_errors() {
+        let input = indoc! {"
+            S-
+            |.
+        "};
+
+        assert_eq!(
+            part1(&parse(input).unwrap()).unwrap_err().to_string(),
+            "pipe leads off the map at (1, 0)"
+        );
+
+        let input = indoc! {"
+            S-7
+            |.|
+            L-.
+        "};
+
+        assert_eq!(
+            part2(&parse(input).unwrap()),
+            Err(MazeError::Broken((2, 1), (2, 2)))
+        );
+    }
+
+    #[test]
+    fn analysis() {
+        let input = indoc! {"
+            F-----7.S7
+            |F---7|.LJ
+            ||F7.||...
+            ||LJ.||F7.
+            |L---J|LJ.
+            L-----J.-.
+        "};
+
+        let analysis = Analysis::new(&parse(input).unwrap());
+        assert_eq!(
+            analysis.cycles,
+            [
+                Cycle {
+                    vertices: vec![(0, 0), (5, 0), (5, 6), (0, 6)],
+                    length: 22,
+                    area: 20,
+                    parent: None,
+                },
+                Cycle {
+                    vertices: vec![(0, 8), (1, 8), (1, 9), (0, 9)],
+                    length: 4,
+                    area: 0,
+                    parent: None,
+                },
+                Cycle {
+                    vertices: vec![(1, 1), (4, 1), (4, 5), (1, 5)],
+                    length: 14,
+                    area: 6,
+                    parent: Some(0),
+                },
+                Cycle {
+                    vertices: vec![(2, 2), (3, 2), (3, 3), (2, 3)],
+                    length: 4,
+                    area: 0,
+                    parent: Some(2),
+                },
+                Cycle {
+                    vertices: vec![(3, 7), (4, 7), (4, 8), (3, 8)],
+                    length: 4,
+                    area: 0,
+                    parent: None,
+                },
+            ]
+        );
+        assert_eq!(
+            analysis.to_string(),
+            indoc! {"
+                loop 0: length 22, encloses 20, 4 corners from (0, 0), outermost
+                loop 1: length 4, encloses 0, 4 corners from (0, 8), outermost
+                loop 2: length 14, encloses 6, 4 corners from (1, 1), inside loop 0
+                loop 3: length 4, encloses 0, 4 corners from (2, 2), inside loop 2
+                loop 4: length 4, encloses 0, 4 corners from (3, 7), outermost
+            "}
+        );
+
+        // A start without a shape still leaves the other loops to analyse.
+        let input = input.replace("S7", "S.");
+        let analysis = Analysis::new(&parse(&input).unwrap());
+        assert_eq!(analysis.cycles.len(), 4);
+        assert_eq!(analysis.cycles[2].parent, Some(1));
+
+        // Open chains are only walked once, even when found at the end they lead away from.
+        let input = format!("{}F7\nLJ\n", "|.\n".repeat(100_000));
+        let analysis = Analysis::new(&parse(&input).unwrap());
+        assert_eq!(analysis.cycles.len(), 1);
+        assert_eq!(analysis.cycles[0].vertices[0], (100_000, 0));
+
+        let grid = parse(&input).unwrap();
+        let mut path = Vec::new();
+        assert_eq!(
+            trace(&grid, (5, 0), Dir::South, &mut path),
+            Err(MazeError::Broken((99_999, 0), (100_000, 0)))
+        );
+        assert_eq!(path.len(), 99_995);
     }
 }
@@ -1,64 +1,164 @@
+use crate::grid::{one_of, parse_grid_with, Grid};
+use crate::number::{self, Int, Overflow};
+use crate::parse::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
-use grid::Grid;
 use itertools::Itertools;
-use std::cmp::{max, min};
+use num_traits::Zero;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
 
 #[aoc_generator(day11)]
-fn parse(input: &str) -> Grid<char> {
-    let mut size = 0;
-    Grid::from_vec(
-        input
-            .lines()
-            .flat_map(|line| {
-                size = line.len();
-                line.chars()
-            })
-            .collect(),
-        size,
-    )
+fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+    parse_grid_with(&Source::new(11, input), input, one_of(".#"))
+}
+
+/// How much the empty rows or columns of the image grow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Axis {
+    /// The width each empty line grows to.
+    pub factor: u64,
+    /// Extra multipliers, one for every line of the image.
+    pub weights: Option<Vec<u64>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ExpansionError {
+    Weights {
+        axis: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    Overflow(Overflow),
+}
+
+impl From<Overflow> for ExpansionError {
+    fn from(e: Overflow) -> Self {
+        ExpansionError::Overflow(e)
+    }
+}
+
+impl Display for ExpansionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpansionError::Weights {
+                axis,
+                expected,
+                found,
+            } => write!(f, "expected {expected} {axis} weights, found {found}"),
+            ExpansionError::Overflow(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl Error for ExpansionError {}
+
+impl Axis {
+    pub fn uniform(factor: u64) -> Self {
+        Self {
+            factor,
+            weights: None,
+        }
+    }
+
+    /// Maps every line to its expanded coordinate, the total width of the lines before it.
+    fn offsets(&self, axis: &'static str, occupied: &[bool]) -> Result<Vec<Int>, ExpansionError> {
+        if let Some(weights) = &self.weights {
+            if weights.len() != occupied.len() {
+                return Err(ExpansionError::Weights {
+                    axis,
+                    expected: occupied.len(),
+                    found: weights.len(),
+                });
+            }
+        }
+
+        let mut offsets = vec![Int::zero()];
+        // The width of the last line never counts towards a coordinate.
+        let lines = occupied.len().saturating_sub(1);
+        for (i, &occupied) in occupied.iter().enumerate().take(lines) {
+            let width = if occupied { 1 } else { self.factor };
+            let weight = self.weights.as_ref().map_or(1, |weights| weights[i]);
+            let width = number::mul(&Int::from(width), &Int::from(weight))?;
+            offsets.push(number::add(&offsets[i], &width)?);
+        }
+        Ok(offsets)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expansion {
+    pub rows: Axis,
+    pub cols: Axis,
+}
+
+impl Expansion {
+    pub fn uniform(factor: u64) -> Self {
+        Self {
+            rows: Axis::uniform(factor),
+            cols: Axis::uniform(factor),
+        }
+    }
 }
 
 #[aoc(day11, part1)]
-fn part1(input: &Grid<char>) -> usize {
-    find_expanded_distances(input, 2)
+fn part1(input: &Grid<char>) -> Result<Int, ExpansionError> {
+    find_expanded_distances(input, &Expansion::uniform(2))
 }
 
 #[aoc(day11, part2)]
-fn part2(input: &Grid<char>) -> usize {
-    find_expanded_distances(input, 1000000)
+fn part2(input: &Grid<char>) -> Result<Int, ExpansionError> {
+    find_expanded_distances(input, &Expansion::uniform(1000000))
 }
 
-fn find_expanded_distances(grid: &Grid<char>, expansion: usize) -> usize {
-    let expanded_cols = grid
-        .iter_cols()
-        .enumerate()
-        .filter(|(_, col)| !col.clone().contains(&'#'))
-        .map(|(i, _)| i)
-        .collect_vec();
-
-    let expanded_rows = grid
-        .iter_rows()
-        .enumerate()
-        .filter(|(_, row)| !row.clone().contains(&'#'))
-        .map(|(i, _)| i)
-        .collect_vec();
+fn find_expanded_distances(
+    grid: &Grid<char>,
+    expansion: &Expansion,
+) -> Result<Int, ExpansionError> {
+    let rows = expansion.rows.offsets(
+        "row",
+        &grid
+            .iter_rows()
+            .map(|mut row| row.contains(&'#'))
+            .collect_vec(),
+    )?;
+    let cols = expansion.cols.offsets(
+        "column",
+        &grid
+            .iter_cols()
+            .map(|mut col| col.contains(&'#'))
+            .collect_vec(),
+    )?;
 
-    grid.indexed_iter()
+    let galaxies = grid
+        .indexed_iter()
         .filter(|(_, c)| **c == '#')
         .map(|(pos, _)| pos)
-        .collect_vec()
-        .iter()
-        .tuple_combinations()
-        .map(|((y1, x1), (y2, x2))| {
-            let (x1, x2) = (min(x1, x2), max(x1, x2));
-            let (y1, y2) = (min(y1, y2), max(y1, y2));
-            ((x2 + expanded_cols.iter().filter(|&i| i > x1 && i < x2).count() * (expansion - 1))
-                - x1)
-                + ((y2
-                    + expanded_rows.iter().filter(|&i| i > y1 && i < y2).count() * (expansion - 1))
-                    - y1)
-        })
-        .sum()
+        .collect_vec();
+    let mut ys = galaxies.iter().map(|&(row, _)| &rows[row]).collect_vec();
+    let mut xs = galaxies.iter().map(|&(_, col)| &cols[col]).collect_vec();
+    ys.sort_unstable();
+    xs.sort_unstable();
+
+    Ok(number::add(&pair_distances(&ys)?, &pair_distances(&xs)?)?)
+}
+
+/// Sums the distances between every pair of galaxies in a day 11 image,
+/// expanded by separate row and column factors and optional per-line weights.
+pub fn galaxy_distances(input: &str, expansion: &Expansion) -> Result<Int, Box<dyn Error>> {
+    Ok(find_expanded_distances(&parse(input)?, expansion)?)
+}
+
+/// Sums the distances between every pair of sorted coordinates: the gap after
+/// the k-th smallest lies between k coordinates on one side and the rest on the other.
+fn pair_distances(coords: &[&Int]) -> Result<Int, Overflow> {
+    let n = coords.len() as u64;
+    number::sum(
+        coords
+            .iter()
+            .tuple_windows()
+            .zip(1..)
+            .map(|((&a, &b), k)| number::mul(&(b - a), &Int::from(k * (n - k)))),
+    )
 }
 
 #[cfg(test)]
@@ -95,7 +195,7 @@ mod tests {
             ['#', '.', '.', '.', '#', '.', '.', '.', '.', '.']
         ];
 
-        assert_eq!(parse(input), result);
+        assert_eq!(parse(input), Ok(result));
     }
 
     #[test]
@@ -113,10 +213,90 @@ mod tests {
             #...#.....
         "};
 
-        let parse = parse(input);
+        let parse = parse(input).unwrap();
+
+        assert_eq!(
+            find_expanded_distances(&parse, &Expansion::uniform(2)),
+            Ok(Int::from(374u64))
+        );
+        assert_eq!(
+            find_expanded_distances(&parse, &Expansion::uniform(10)),
+            Ok(Int::from(1030u64))
+        );
+        assert_eq!(
+            find_expanded_distances(&parse, &Expansion::uniform(100)),
+            Ok(Int::from(8410u64))
+        );
+
+        #[cfg(not(feature = "bigint"))]
+        assert_eq!(
+            find_expanded_distances(&parse, &Expansion::uniform(u64::MAX)),
+            Err(ExpansionError::Overflow(Overflow))
+        );
+        #[cfg(feature = "bigint")]
+        assert_eq!(
+            find_expanded_distances(&parse, &Expansion::uniform(u64::MAX)),
+            Ok(Int::from(u64::MAX - 1) * 82u64 + 292u64)
+        );
+    }
+
+    #[test]
+    fn per_axis_expansion() {
+        let input = indoc! {"
+            ...#......
+            .......#..
+            #.........
+            ..........
+            ......#...
+            .#........
+            .........#
+            ..........
+            .......#..
+            #...#.....
+        "};
+
+        let parse = parse(input).unwrap();
+        let expansion = |rows, cols| Expansion {
+            rows: Axis::uniform(rows),
+            cols: Axis::uniform(cols),
+        };
+
+        assert_eq!(
+            find_expanded_distances(&parse, &expansion(1, 1)),
+            Ok(Int::from(292u64))
+        );
+        assert_eq!(
+            find_expanded_distances(&parse, &expansion(2, 1)),
+            Ok(Int::from(328u64))
+        );
+        assert_eq!(
+            find_expanded_distances(&parse, &expansion(1, 2)),
+            Ok(Int::from(338u64))
+        );
+
+        // Weighting the empty rows by 2 matches growing them by 2.
+        let mut weights = vec![1; 10];
+        weights[3] = 2;
+        weights[7] = 2;
+        let weighted = Expansion {
+            rows: Axis {
+                factor: 1,
+                weights: Some(weights),
+            },
+            cols: Axis::uniform(1),
+        };
+        assert_eq!(
+            find_expanded_distances(&parse, &weighted),
+            Ok(Int::from(328u64))
+        );
 
-        assert_eq!(find_expanded_distances(&parse, 2), 374);
-        assert_eq!(find_expanded_distances(&parse, 10), 1030);
-        assert_eq!(find_expanded_distances(&parse, 100), 8410);
+        let mut short = weighted.clone();
+        short.cols.weights = Some(vec![1; 9]);
+        assert_eq!(
+            find_expanded_distances(&parse, &short)
+                .unwrap_err()
+                .to_string(),
+            "expected 10 column weights, found 9"
+        );
     }
 }
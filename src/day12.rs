@@ -1,40 +1,48 @@
+use crate::parse::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
-use michie::memoized;
 use std::collections::HashMap;
 
+type Record = (Vec<char>, Vec<u32>);
+
 #[aoc_generator(day12)]
-fn parse(input: &str) -> Vec<(usize, Vec<char>, Vec<u32>)> {
+fn parse(input: &str) -> Result<Vec<Record>, ParseError> {
+    let source = Source::new(12, input);
     input
         .lines()
-        .enumerate()
-        .map(|(i, line)| {
-            let (left, right) = line.split_once(' ').unwrap();
-            (
-                i,
-                left.chars().collect(),
+        .map(|line| {
+            let (left, right) = source.split_once(line, " ")?;
+            Ok((
+                Source::chars(left)
+                    .map(|(c, at)| match c {
+                        '.' | '#' | '?' => Ok(c),
+                        _ => Err(source.error(at, format!("unknown spring {c:?}"))),
+                    })
+                    .collect::<Result<_, _>>()?,
                 right
                     .split(',')
-                    .map(str::parse)
-                    .filter_map(Result::ok)
-                    .collect(),
-            )
+                    .map(|group| match source.parse(group)? {
+                        0 => Err(source.error(group, "group size must be positive")),
+                        size => Ok(size),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ))
         })
         .collect()
 }
 
 #[aoc(day12, part1)]
-fn part1(input: &[(usize, Vec<char>, Vec<u32>)]) -> usize {
+fn part1(input: &[Record]) -> usize {
     input
         .iter()
-        .map(|(i, states, groups)| solve(*i, states, groups, 0, 0))
+        .map(|(states, groups)| solve(states, groups, 0, 0, &mut HashMap::new()))
         .sum()
 }
 
 #[aoc(day12, part2)]
-fn part2(input: &[(usize, Vec<char>, Vec<u32>)]) -> usize {
+fn part2(input: &[Record]) -> usize {
     input
         .iter()
-        .map(|(i, states, groups)| {
+        .map(|(states, groups)| {
             let states = {
                 let mut states = states.clone();
                 states.push('?');
@@ -42,19 +50,35 @@ fn part2(input: &[(usize, Vec<char>, Vec<u32>)]) -> usize {
                 states.pop();
                 states
             };
-            solve(*i + input.len(), &states, &groups.repeat(5), 0, 0)
+            solve(&states, &groups.repeat(5), 0, 0, &mut HashMap::new())
         })
         .sum()
 }
 
 // Taken from: https://github.com/alcatrazEscapee/AdventOfCode/blob/main/2023/src/day12.cor#L12-L104
-#[memoized(key_expr = (i, states_index, groups_index), store_type = HashMap<(usize, usize, usize), usize>)]
+/// Counts the arrangements of one record, memoised in `cache` by where the
+/// search stands in its springs and groups.
 fn solve(
-    i: usize,
     states: &[char],
     groups: &[u32],
     states_index: usize,
     groups_index: usize,
+    cache: &mut HashMap<(usize, usize), usize>,
+) -> usize {
+    if let Some(&n) = cache.get(&(states_index, groups_index)) {
+        return n;
+    }
+    let n = count(states, groups, states_index, groups_index, cache);
+    cache.insert((states_index, groups_index), n);
+    n
+}
+
+fn count(
+    states: &[char],
+    groups: &[u32],
+    states_index: usize,
+    groups_index: usize,
+    cache: &mut HashMap<(usize, usize), usize>,
 ) -> usize {
     let mut states_index = states_index;
     let mut groups_index = groups_index;
@@ -72,7 +96,7 @@ fn solve(
 
     let mut n = 0;
     if states[states_index] == '?' {
-        n += solve(i, states, groups, states_index + 1, groups_index);
+        n += solve(states, groups, states_index + 1, groups_index, cache);
     }
 
     if groups_index >= groups.len() {
@@ -104,7 +128,7 @@ fn solve(
         return n;
     }
 
-    n += solve(i, states, groups, states_index + 1, groups_index);
+    n += solve(states, groups, states_index + 1, groups_index, cache);
 
     n
 }
@@ -126,30 +150,26 @@ mod tests {
         "};
 
         let result = vec![
-            (0, vec!['?', '?', '?', '.', '#', '#', '#'], vec![1, 1, 3]),
+            (vec!['?', '?', '?', '.', '#', '#', '#'], vec![1, 1, 3]),
             (
-                1,
                 vec![
                     '.', '?', '?', '.', '.', '?', '?', '.', '.', '.', '?', '#', '#', '.',
                 ],
                 vec![1, 1, 3],
             ),
             (
-                2,
                 vec![
                     '?', '#', '?', '#', '?', '#', '?', '#', '?', '#', '?', '#', '?', '#', '?',
                 ],
                 vec![1, 3, 1, 6],
             ),
             (
-                3,
                 vec![
                     '?', '?', '?', '?', '.', '#', '.', '.', '.', '#', '.', '.', '.',
                 ],
                 vec![4, 1, 1],
             ),
             (
-                4,
                 vec![
                     '?', '?', '?', '?', '.', '#', '#', '#', '#', '#', '#', '.', '.', '#', '#', '#',
                     '#', '#', '.',
@@ -157,13 +177,12 @@ mod tests {
                 vec![1, 6, 5],
             ),
             (
-                5,
                 vec!['?', '#', '#', '#', '?', '?', '?', '?', '?', '?', '?', '?'],
                 vec![3, 2, 1],
             ),
         ];
 
-        assert_eq!(parse(input), result);
+        assert_eq!(parse(input), Ok(result));
     }
 
     #[test]
@@ -177,7 +196,7 @@ mod tests {
             ?###???????? 3,2,1
         "};
 
-        assert_eq!(part1(&parse(input)), 21);
+        assert_eq!(part1(&parse(input).unwrap()), 21);
     }
 
     #[test]
@@ -191,6 +210,17 @@ mod tests {
             ?###???????? 3,2,1
         "};
 
-        assert_eq!(part2(&parse(input)), 525152);
+        assert_eq!(part2(&parse(input).unwrap()), 525152);
+    }
+
+    #[test]
+    fn separate_inputs() {
+        let first = parse("?###???????? 3,2,1\n").unwrap();
+        let second = parse("???.### 1,1,3\n.??..??...?##. 1,1,3\n").unwrap();
+
+        assert_eq!(part1(&first), 10);
+        assert_eq!(part1(&second), 5);
+        assert_eq!(part2(&first), 506250);
+        assert_eq!(part2(&second), 16385);
     }
 }
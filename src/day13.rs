@@ -1,25 +1,16 @@
+use crate::grid::{one_of, parse_grid_with, Grid};
+use crate::parse::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
-use grid::Grid;
 use itertools::Itertools;
 use std::iter::StepBy;
 use std::slice::Iter;
 
 #[aoc_generator(day13)]
-fn parse(input: &str) -> Vec<Grid<char>> {
+fn parse(input: &str) -> Result<Vec<Grid<char>>, ParseError> {
+    let source = Source::new(13, input);
     input
         .split("\n\n")
-        .map(|grid| {
-            let mut size = 0;
-            Grid::from_vec(
-                grid.lines()
-                    .flat_map(|line| {
-                        size = line.len();
-                        line.chars()
-                    })
-                    .collect(),
-                size,
-            )
-        })
+        .map(|grid| parse_grid_with(&source, grid, one_of(".#")))
         .collect()
 }
 
@@ -145,7 +136,7 @@ mod tests {
             ],
         ];
 
-        assert_eq!(parse(input), result);
+        assert_eq!(parse(input), Ok(result));
     }
 
     #[test]
@@ -168,7 +159,7 @@ mod tests {
             #....#..#
         "};
 
-        assert_eq!(part1(&parse(input)), 405);
+        assert_eq!(part1(&parse(input).unwrap()), 405);
     }
 
     #[test]
@@ -191,6 +182,6 @@ mod tests {
             #....#..#
         "};
 
-        assert_eq!(part2(&parse(input)), 400);
+        assert_eq!(part2(&parse(input).unwrap()), 400);
     }
 }
@@ -1,21 +1,12 @@
+use crate::cycle::find_cycle;
+use crate::grid::{one_of, parse_grid_with, Grid};
+use crate::parse::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
-use grid::Grid;
-use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
 #[aoc_generator(day14)]
-fn parse(input: &str) -> Grid<char> {
-    let mut size = 0;
-    Grid::from_vec(
-        input
-            .lines()
-            .flat_map(|line| {
-                size = line.len();
-                line.chars()
-            })
-            .collect(),
-        size,
-    )
+fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+    parse_grid_with(&Source::new(14, input), input, one_of(".#O"))
 }
 
 #[aoc(day14, part1)]
@@ -36,20 +27,12 @@ impl Hash for HashableGrid {
 
 #[aoc(day14, part2)]
 fn part2(input: &Grid<char>) -> usize {
-    let mut input = HashableGrid(input.clone());
-    let mut map = HashMap::new();
-    let mut i = 0;
-    while !map.contains_key(&input) {
-        map.insert(input.clone(), i);
-        cycle(&mut input.0);
-        i += 1;
-    }
-    let cycle_start = (1000000000 - i) % (i - map[&input]) + map[&input];
-    if let Some((grid, _)) = map.iter().find(|(_, i)| **i == cycle_start) {
-        beam_load(&grid.0)
-    } else {
-        0
-    }
+    let history = find_cycle(HashableGrid(input.clone()), |grid| {
+        let mut grid = grid.clone();
+        cycle(&mut grid.0);
+        grid
+    });
+    beam_load(&history.state_at(1000000000).0)
 }
 
 fn beam_load(grid: &Grid<char>) -> usize {
@@ -138,7 +121,7 @@ mod tests {
             #OO..#....
         "};
 
-        assert_eq!(parse(input), *EXAMPLE_GRID);
+        assert_eq!(parse(input), Ok(EXAMPLE_GRID.clone()));
     }
 
     #[test]
@@ -156,7 +139,7 @@ mod tests {
             #OO..#....
         "};
 
-        assert_eq!(part1(&parse(input)), 136);
+        assert_eq!(part1(&parse(input).unwrap()), 136);
     }
 
     #[test]
@@ -174,7 +157,7 @@ mod tests {
             #OO..#....
         "};
 
-        assert_eq!(part2(&parse(input)), 64);
+        assert_eq!(part2(&parse(input).unwrap()), 64);
     }
 
     #[test]
@@ -192,7 +175,7 @@ mod tests {
             #.OOO#..OO
         "};
 
-        assert_eq!(beam_load(&parse(grid)), 64);
+        assert_eq!(beam_load(&parse(grid).unwrap()), 64);
     }
 
     #[test]
@@ -0,0 +1,237 @@
+use crate::parse::{ParseError, Source};
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+pub use ::grid::Grid;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Pos {
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// Moves by the given offset, returning `None` if that would leave the first quadrant.
+    pub fn offset(self, (rows, cols): (isize, isize)) -> Option<Self> {
+        Some(Self {
+            row: self.row.checked_add_signed(rows)?,
+            col: self.col.checked_add_signed(cols)?,
+        })
+    }
+
+    pub fn step(self, dir: Dir) -> Option<Self> {
+        self.offset(dir.delta())
+    }
+}
+
+impl From<(usize, usize)> for Pos {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self { row, col }
+    }
+}
+
+impl From<Pos> for (usize, usize) {
+    fn from(pos: Pos) -> Self {
+        (pos.row, pos.col)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Dir {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Dir {
+    pub const ALL: [Dir; 4] = [Dir::North, Dir::East, Dir::South, Dir::West];
+
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Dir::North => (-1, 0),
+            Dir::East => (0, 1),
+            Dir::South => (1, 0),
+            Dir::West => (0, -1),
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Dir::North => Dir::South,
+            Dir::East => Dir::West,
+            Dir::South => Dir::North,
+            Dir::West => Dir::East,
+        }
+    }
+}
+
+const NEIGHBOURS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        &self[(pos.row, pos.col)]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        &mut self[(pos.row, pos.col)]
+    }
+}
+
+pub trait GridExt<T> {
+    fn contains_pos(&self, pos: Pos) -> bool;
+
+    /// Steps from `pos` in `dir`, returning `None` if that leaves the grid.
+    fn step(&self, pos: Pos, dir: Dir) -> Option<Pos>;
+
+    fn positions(&self) -> impl Iterator<Item = Pos>;
+
+    fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = (Dir, Pos)>;
+
+    fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos>;
+}
+
+impl<T> GridExt<T> for Grid<T> {
+    fn contains_pos(&self, pos: Pos) -> bool {
+        pos.row < self.rows() && pos.col < self.cols()
+    }
+
+    fn step(&self, pos: Pos, dir: Dir) -> Option<Pos> {
+        pos.step(dir).filter(|&pos| self.contains_pos(pos))
+    }
+
+    fn positions(&self) -> impl Iterator<Item = Pos> {
+        let cols = self.cols();
+        (0..self.rows() * cols).map(move |i| Pos::new(i / cols, i % cols))
+    }
+
+    fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = (Dir, Pos)> {
+        Dir::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir).map(|pos| (dir, pos)))
+    }
+
+    fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        NEIGHBOURS8
+            .into_iter()
+            .filter_map(move |delta| pos.offset(delta))
+            .filter(|&pos| self.contains_pos(pos))
+    }
+}
+
+/// Parses a block of equally long lines, converting each char with `cell`.
+pub fn parse_grid_with<'a, T, E: Display>(
+    source: &Source<'a>,
+    block: &'a str,
+    cell: impl Fn(char) -> Result<T, E>,
+) -> Result<Grid<T>, ParseError> {
+    let mut cols = None;
+    let mut cells = Vec::new();
+    for line in block.lines() {
+        let len = line.chars().count();
+        if *cols.get_or_insert(len) != len {
+            return Err(source.error(line, "line length differs from the first line"));
+        }
+        for (c, at) in Source::chars(line) {
+            cells.push(cell(c).map_err(|e| source.error(at, e.to_string()))?);
+        }
+    }
+    Ok(Grid::from_vec(cells, cols.unwrap_or_default()))
+}
+
+pub fn parse_grid<'a, T>(source: &Source<'a>, block: &'a str) -> Result<Grid<T>, ParseError>
+where
+    T: TryFrom<char>,
+    T::Error: Display,
+{
+    parse_grid_with(source, block, T::try_from)
+}
+
+/// A cell parser for `Grid<char>` that only accepts the given chars.
+pub fn one_of(allowed: &str) -> impl Fn(char) -> Result<char, String> + '_ {
+    move |c| {
+        if allowed.contains(c) {
+            Ok(c)
+        } else {
+            Err(format!("unknown char {c:?}"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::grid::grid;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_grid_example() {
+        let input = indoc! {"
+            #..
+            .#.
+        "};
+        let source = Source::new(0, input);
+
+        assert_eq!(
+            parse_grid(&source, input),
+            Ok(grid![['#', '.', '.']['.', '#', '.']])
+        );
+        assert_eq!(
+            parse_grid_with(&source, input, one_of(".")),
+            Err(ParseError {
+                day: 0,
+                line: 1,
+                column: 1,
+                text: "#".into(),
+                message: "unknown char '#'".into(),
+            })
+        );
+
+        let input = indoc! {"
+            #..
+            .#
+        "};
+        let source = Source::new(0, input);
+
+        assert_eq!(
+            parse_grid::<char>(&source, input).unwrap_err().message,
+            "line length differs from the first line"
+        );
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid: Grid<u8> = Grid::new(3, 4);
+
+        assert_eq!(
+            grid.neighbours4(Pos::new(0, 0)).collect::<Vec<_>>(),
+            vec![(Dir::East, Pos::new(0, 1)), (Dir::South, Pos::new(1, 0))]
+        );
+        assert_eq!(grid.neighbours4(Pos::new(1, 1)).count(), 4);
+        assert_eq!(
+            grid.neighbours8(Pos::new(2, 3)).collect::<Vec<_>>(),
+            vec![Pos::new(1, 2), Pos::new(1, 3), Pos::new(2, 2)]
+        );
+        assert_eq!(grid.neighbours8(Pos::new(1, 1)).count(), 8);
+        assert_eq!(grid.step(Pos::new(2, 3), Dir::East), None);
+        assert_eq!(grid.step(Pos::new(0, 3), Dir::North), None);
+        assert_eq!(grid.positions().nth(5), Some(Pos::new(1, 1)));
+    }
+}
@@ -0,0 +1,268 @@
+use std::ops::Range;
+
+/// Maps `source` onto the equally long range starting at `dest`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Segment {
+    pub source: Range<u64>,
+    pub dest: u64,
+}
+
+impl Segment {
+    fn map(&self, value: u64) -> u64 {
+        self.dest + (value - self.source.start)
+    }
+}
+
+/// A piecewise map over `u64` built from sorted, non-overlapping segments.
+/// Values outside of every segment map to themselves.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct IntervalMap {
+    segments: Vec<Segment>,
+}
+
+impl IntervalMap {
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Inserts a segment, returning the segment it overlaps with on failure.
+    pub fn insert(&mut self, segment: Segment) -> Result<(), Segment> {
+        if segment.source.is_empty() {
+            return Ok(());
+        }
+        let index = self
+            .segments
+            .partition_point(|s| s.source.end <= segment.source.start);
+        if let Some(next) = self.segments.get(index) {
+            if next.source.start < segment.source.end {
+                return Err(next.clone());
+            }
+        }
+        self.segments.insert(index, segment);
+        Ok(())
+    }
+
+    fn find(&self, value: u64) -> Option<&Segment> {
+        let index = self.segments.partition_point(|s| s.source.end <= value);
+        self.segments
+            .get(index)
+            .filter(|segment| segment.source.contains(&value))
+    }
+
+    pub fn get(&self, value: u64) -> u64 {
+        self.find(value).map_or(value, |segment| segment.map(value))
+    }
+
+    /// Splits `range` at the segment boundaries, pairing each piece with the segment covering it.
+    fn split(&self, range: Range<u64>) -> Vec<(Range<u64>, Option<&Segment>)> {
+        let mut result = Vec::new();
+        let mut start = range.start;
+        let first = self.segments.partition_point(|s| s.source.end <= start);
+        for segment in &self.segments[first..] {
+            if start >= range.end {
+                break;
+            }
+            if segment.source.start >= range.end {
+                break;
+            }
+            if start < segment.source.start {
+                result.push((start..segment.source.start, None));
+                start = segment.source.start;
+            }
+            let end = segment.source.end.min(range.end);
+            result.push((start..end, Some(segment)));
+            start = end;
+        }
+        if start < range.end {
+            result.push((start..range.end, None));
+        }
+        result
+    }
+
+    /// Maps every value of `range`, returning the image as ranges in the order of their sources.
+    pub fn map_range(&self, range: Range<u64>) -> Vec<Range<u64>> {
+        self.split(range)
+            .into_iter()
+            .map(|(piece, segment)| match segment {
+                Some(segment) => {
+                    segment.map(piece.start)..segment.map(piece.start) + (piece.end - piece.start)
+                }
+                None => piece,
+            })
+            .collect()
+    }
+
+    /// Returns every value where the map stops being a single shift, in ascending order.
+    pub fn breakpoints(&self) -> Vec<u64> {
+        let mut points: Vec<u64> = self
+            .segments
+            .iter()
+            .flat_map(|segment| [segment.source.start, segment.source.end])
+            .collect();
+        points.dedup();
+        points
+    }
+
+    /// Returns every value that maps onto `value`, in ascending order.
+    pub fn preimage(&self, value: u64) -> Vec<u64> {
+        let mut result: Vec<u64> = self
+            .segments
+            .iter()
+            .filter(|segment| {
+                value >= segment.dest
+                    && value - segment.dest < segment.source.end - segment.source.start
+            })
+            .map(|segment| segment.source.start + (value - segment.dest))
+            .collect();
+        if self.find(value).is_none() {
+            result.push(value);
+        }
+        result.sort_unstable();
+        result
+    }
+
+    /// Returns the smallest value any of `ranges` maps onto.
+    pub fn min_over<'a>(&self, ranges: impl IntoIterator<Item = &'a Range<u64>>) -> Option<u64> {
+        ranges
+            .into_iter()
+            .flat_map(|range| self.map_range(range.clone()))
+            .map(|range| range.start)
+            .min()
+    }
+
+    /// Returns the map equivalent to applying `self` and then `next`.
+    pub fn then(&self, next: &IntervalMap) -> IntervalMap {
+        let mut segments = Vec::new();
+
+        for segment in &self.segments {
+            let image = segment.dest..segment.map(segment.source.end - 1) + 1;
+            for (piece, next_segment) in next.split(image) {
+                let start = segment.source.start + (piece.start - segment.dest);
+                segments.push(Segment {
+                    source: start..start + (piece.end - piece.start),
+                    dest: next_segment.map_or(piece.start, |s| s.map(piece.start)),
+                });
+            }
+        }
+
+        for next_segment in &next.segments {
+            for (piece, segment) in self.split(next_segment.source.clone()) {
+                if segment.is_none() {
+                    segments.push(Segment {
+                        dest: next_segment.map(piece.start),
+                        source: piece,
+                    });
+                }
+            }
+        }
+
+        segments.sort_by_key(|segment| segment.source.start);
+
+        let mut result: Vec<Segment> = Vec::new();
+        for segment in segments {
+            if segment.dest == segment.source.start {
+                continue;
+            }
+            match result.last_mut() {
+                Some(last)
+                    if last.source.end == segment.source.start
+                        && last.map(last.source.end - 1) + 1 == segment.dest =>
+                {
+                    last.source.end = segment.source.end
+                }
+                _ => result.push(segment),
+            }
+        }
+
+        IntervalMap { segments: result }
+    }
+}
+
+impl FromIterator<(Range<u64>, u64)> for IntervalMap {
+    /// Panics if two of the segments overlap.
+    fn from_iter<I: IntoIterator<Item = (Range<u64>, u64)>>(iter: I) -> Self {
+        let mut map = IntervalMap::default();
+        for (source, dest) in iter {
+            map.insert(Segment { source, dest })
+                .expect("segments must not overlap");
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_get() {
+        let mut map = IntervalMap::from_iter([(98..100, 50), (50..98, 52)]);
+
+        assert_eq!(map.get(0), 0);
+        assert_eq!(map.get(49), 49);
+        assert_eq!(map.get(50), 52);
+        assert_eq!(map.get(97), 99);
+        assert_eq!(map.get(98), 50);
+        assert_eq!(map.get(99), 51);
+        assert_eq!(map.get(100), 100);
+        assert_eq!(
+            map.insert(Segment {
+                source: 40..51,
+                dest: 0
+            }),
+            Err(Segment {
+                source: 50..98,
+                dest: 52
+            })
+        );
+        assert_eq!(map.segments().len(), 2);
+    }
+
+    #[test]
+    fn exact_for_large_values() {
+        let map = IntervalMap::from_iter([(u64::MAX - 10..u64::MAX, 1 << 60)]);
+
+        assert_eq!(map.get(u64::MAX - 1), (1 << 60) + 9);
+        assert_eq!(map.get(u64::MAX - 10), 1 << 60);
+    }
+
+    #[test]
+    fn then() {
+        let first = IntervalMap::from_iter([(2..5, 7), (7..10, 2)]);
+        let second = IntervalMap::from_iter([(0..4, 20), (8..9, 0)]);
+        let composed = first.then(&second);
+
+        for value in 0..30 {
+            assert_eq!(composed.get(value), second.get(first.get(value)));
+        }
+        assert_eq!(first.then(&first), IntervalMap::default());
+    }
+
+    #[test]
+    fn breakpoints() {
+        let map = IntervalMap::from_iter([(2..5, 7), (5..7, 0), (9..10, 2)]);
+
+        assert_eq!(map.breakpoints(), vec![2, 5, 7, 9, 10]);
+        assert_eq!(IntervalMap::default().breakpoints(), vec![]);
+    }
+
+    #[test]
+    fn preimage() {
+        let map = IntervalMap::from_iter([(2..5, 7), (7..10, 5)]);
+
+        for value in 0..15 {
+            let expected: Vec<u64> = (0..20).filter(|&x| map.get(x) == value).collect();
+            assert_eq!(map.preimage(value), expected);
+        }
+    }
+
+    #[test]
+    fn min_over() {
+        let map = IntervalMap::from_iter([(2..5, 7), (7..10, 0)]);
+
+        assert_eq!(map.min_over(&[0..3, 3..3]), Some(0));
+        assert_eq!(map.min_over(&[2..5, 9..12]), Some(2));
+        assert_eq!(map.min_over(&[3..4, 5..7]), Some(5));
+        assert_eq!(map.min_over(&[]), None);
+    }
+}
@@ -0,0 +1,34 @@
+use aoc_runner_derive::aoc_lib;
+
+pub mod answers;
+pub mod bench;
+pub mod cycle;
+mod day01;
+mod day02;
+mod day03;
+mod day04;
+mod day05;
+mod day06;
+mod day07;
+mod day08;
+mod day09;
+mod day10;
+mod day11;
+mod day12;
+mod day13;
+mod day14;
+mod day15;
+mod grid;
+pub mod interval;
+pub mod number;
+mod parse;
+pub mod registry;
+pub mod report;
+
+pub use day07::audit_hands;
+pub use day08::{analyse_ghosts, Analysis as GhostAnalysis, Ghost};
+pub use day09::{extrapolate, extrapolate_confirmed, extrapolate_sequences, SequenceError};
+pub use day10::{analyse_loops, render, Analysis as LoopAnalysis, Backend, Cycle};
+pub use day11::{galaxy_distances, Axis, Expansion};
+
+aoc_lib! { year = 2023 }
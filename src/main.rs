@@ -1,19 +1,752 @@
-use aoc_runner_derive::aoc_main;
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-
-aoc_main! { year = 2023 }
+use aoc_2023::answers::{self, Verdict};
+use aoc_2023::bench::{self, Measurement, Stats};
+use aoc_2023::registry::{self, Answer, Solution, SOLUTIONS};
+use aoc_2023::report::{self, Entry};
+use aoc_2023::{Backend, Expansion, YEAR};
+use indoc::indoc;
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::ExitCode;
+use std::{env, fs, io};
+
+const USAGE: &str = indoc! {"
+    Usage: aoc_2023 [COMMAND]
+
+    Commands:
+      run [--day <DAY>] [--part <PART>] [--method <NAME>] [--input <PATH|->]
+                Run the selected solutions (all of them by default).
+                The input defaults to input/2023/day<DAY>.txt, `-` reads stdin.
+                `--method` picks an alternative solver, as shown by `list`.
+      report [--format <json|csv>] [--day <DAY>] [--part <PART>] [--method <NAME>]
+             [--input <PATH|->]
+                Run the selected solutions and print answers and timings
+                as JSON (the default) or CSV.
+      verify [--manifest <PATH>] [--day <DAY>] [--part <PART>]
+                Check the solutions against the expected answers in the
+                manifest (answers.txt by default).
+      bench [--iterations <N>] [--save <PATH>] [--compare <PATH>]
+            [--day <DAY>] [--part <PART>] [--method <NAME>] [--input <PATH|->]
+                Time the generator and the solver of the selected solutions
+                N times each (20 by default), optionally saving the results as
+                a baseline or comparing their medians against a saved one.
+      render [--format <plain|ansi|svg>] [--input <PATH|->]
+                Draw the day 10 pipe loop and the tiles it encloses as plain
+                text (the default), coloured text or SVG.
+      loops [--input <PATH|->]
+                List every closed pipe loop in a day 10 maze with its length,
+                the tiles it encloses and the loop it is nested in.
+      hands [--part <PART>] [--input <PATH|->]
+                Print the ranked day 7 hands with the cards the jokers stand
+                for (part 2), their type and winnings.
+      ghosts [--input <PATH|->]
+                Show where each day 8 ghost can go, the tail and cycle of its
+                walk, the Z nodes on the cycle and the nodes no ghost visits.
+      extrapolate [--at <K>] [--confirmations <N>] [--input <PATH|->]
+                Fit a polynomial through each day 9 sequence and print it with
+                its value at index K (the index after the sequence by default),
+                failing unless N rows (1 by default) follow the first constant
+                row of differences.
+      galaxies [--rows <FACTOR>] [--cols <FACTOR>] [--row-weights <W,W,...>]
+               [--col-weights <W,W,...>] [--input <PATH|->]
+                Sum the day 11 galaxy distances with empty rows and columns
+                grown by separate factors (2 by default), each line optionally
+                multiplied by its weight.
+      list      List all registered solutions
+      help      Print this message
+"};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Input {
+    Default,
+    Stdin,
+    Path(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Selection {
+    day: Option<u8>,
+    part: Option<u8>,
+    method: Option<String>,
+    input: Input,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Format {
+    Json,
+    Csv,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct BenchOptions {
+    iterations: usize,
+    save: Option<String>,
+    compare: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Run(Selection),
+    Report(Selection, Format),
+    Verify(Selection, String),
+    Bench(Selection, BenchOptions),
+    Render(Input, Backend),
+    Loops(Input),
+    Hands(Input, bool),
+    Ghosts(Input),
+    Extrapolate(Input, Option<i128>, usize),
+    Galaxies(Input, Expansion),
+    List,
+    Help,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    let command = args.next();
+    let mut format = None;
+    let mut manifest = None;
+    let mut bench = BenchOptions {
+        iterations: 20,
+        save: None,
+        compare: None,
+    };
+    let mut selection = Selection {
+        day: None,
+        part: None,
+        method: None,
+        input: Input::Default,
+    };
+
+    match command.as_deref() {
+        None | Some("run" | "report" | "verify" | "bench") => {}
+        Some("render" | "loops" | "hands" | "ghosts" | "extrapolate" | "galaxies") => {}
+        Some("list") | Some("help" | "--help" | "-h") => {
+            if let Some(arg) = args.next() {
+                return Err(format!("unexpected argument `{arg}`"));
+            }
+        }
+        Some(other) => return Err(format!("unknown command `{other}`")),
+    }
+
+    // Commands that show more than the answer of a single day.
+    let single_day = matches!(
+        command.as_deref(),
+        Some("render" | "loops" | "hands" | "ghosts" | "extrapolate" | "galaxies")
+    );
+    let mut expansion = Expansion::uniform(2);
+    let mut at = None;
+    let mut confirmations = 1;
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for `{flag}`"));
+        let number = |value: String| {
+            value
+                .parse::<u8>()
+                .map_err(|e| format!("invalid value `{value}` for `{flag}`: {e}"))
+        };
+        match flag.as_str() {
+            "--day" | "-d" if !single_day => selection.day = Some(number(value()?)?),
+            "--part" | "-p" if !single_day || command.as_deref() == Some("hands") => {
+                selection.part = Some(number(value()?)?)
+            }
+            "--method" | "-M"
+                if matches!(command.as_deref(), None | Some("run" | "report" | "bench")) =>
+            {
+                selection.method = Some(value()?)
+            }
+            "--input" | "-i" if command.as_deref() != Some("verify") => {
+                selection.input = match value()?.as_str() {
+                    "-" => Input::Stdin,
+                    path => Input::Path(path.to_string()),
+                }
+            }
+            "--format" | "-f" if matches!(command.as_deref(), Some("report" | "render")) => {
+                format = Some(value()?)
+            }
+            "--manifest" | "-m" if command.as_deref() == Some("verify") => {
+                manifest = Some(value()?)
+            }
+            "--iterations" | "-n" if command.as_deref() == Some("bench") => {
+                let value = value()?;
+                bench.iterations = value
+                    .parse()
+                    .ok()
+                    .filter(|&iterations| iterations > 0)
+                    .ok_or(format!("invalid value `{value}` for `{flag}`"))?;
+            }
+            "--rows" | "--cols" | "--row-weights" | "--col-weights"
+                if command.as_deref() == Some("galaxies") =>
+            {
+                let value = value()?;
+                let invalid = |e| format!("invalid value `{value}` for `{flag}`: {e}");
+                let axis = match flag.as_str() {
+                    "--rows" | "--row-weights" => &mut expansion.rows,
+                    _ => &mut expansion.cols,
+                };
+                if flag.ends_with("weights") {
+                    axis.weights = Some(
+                        value
+                            .split(',')
+                            .map(str::parse)
+                            .collect::<Result<_, _>>()
+                            .map_err(invalid)?,
+                    );
+                } else {
+                    axis.factor = value.parse().map_err(invalid)?;
+                }
+            }
+            "--at" if command.as_deref() == Some("extrapolate") => {
+                let value = value()?;
+                at = Some(
+                    value
+                        .parse()
+                        .map_err(|e| format!("invalid value `{value}` for `{flag}`: {e}"))?,
+                );
+            }
+            "--confirmations" if command.as_deref() == Some("extrapolate") => {
+                let value = value()?;
+                confirmations = value
+                    .parse()
+                    .map_err(|e| format!("invalid value `{value}` for `{flag}`: {e}"))?;
+            }
+            "--save" if command.as_deref() == Some("bench") => bench.save = Some(value()?),
+            "--compare" if command.as_deref() == Some("bench") => bench.compare = Some(value()?),
+            _ => return Err(format!("unexpected argument `{flag}`")),
+        }
+    }
+
+    let format = format.as_deref();
+    let unknown_format = || format!("unknown format `{}`", format.unwrap());
+    if command.as_deref() == Some("render") {
+        let backend = match format {
+            None | Some("plain") => Backend::Plain,
+            Some("ansi") => Backend::Ansi,
+            Some("svg") => Backend::Svg,
+            _ => return Err(unknown_format()),
+        };
+        return Ok(Command::Render(selection.input, backend));
+    }
+    if command.as_deref() == Some("loops") {
+        return Ok(Command::Loops(selection.input));
+    }
+    if command.as_deref() == Some("galaxies") {
+        return Ok(Command::Galaxies(selection.input, expansion));
+    }
+    if command.as_deref() == Some("ghosts") {
+        return Ok(Command::Ghosts(selection.input));
+    }
+    if command.as_deref() == Some("extrapolate") {
+        return Ok(Command::Extrapolate(selection.input, at, confirmations));
+    }
+    if command.as_deref() == Some("hands") {
+        let jokers = match selection.part {
+            None | Some(1) => false,
+            Some(2) => true,
+            Some(part) => return Err(format!("invalid value `{part}` for `--part`")),
+        };
+        return Ok(Command::Hands(selection.input, jokers));
+    }
+    let format = match format {
+        None | Some("json") => Format::Json,
+        Some("csv") => Format::Csv,
+        _ => return Err(unknown_format()),
+    };
+
+    if selection.input != Input::Default && selection.day.is_none() {
+        return Err("`--input` requires `--day`".to_string());
+    }
+
+    Ok(match command.as_deref() {
+        Some("report") => Command::Report(selection, format),
+        Some("bench") => Command::Bench(selection, bench),
+        Some("verify") => Command::Verify(
+            selection,
+            manifest.unwrap_or_else(|| answers::MANIFEST.to_string()),
+        ),
+        Some("list") => Command::List,
+        Some("help" | "--help" | "-h") => Command::Help,
+        _ => Command::Run(selection),
+    })
+}
+
+fn read_input(solution: &Solution, input: &Input) -> Result<String, String> {
+    match input {
+        Input::Default => {
+            let path = solution.default_input();
+            fs::read_to_string(&path).map_err(|e| format!("cannot read {path}: {e}"))
+        }
+        Input::Stdin => {
+            let mut buffer = String::new();
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .map_err(|e| format!("cannot read stdin: {e}"))?;
+            Ok(buffer)
+        }
+        Input::Path(path) => {
+            fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"))
+        }
+    }
+}
+
+fn select(selection: &Selection) -> Result<Vec<&'static Solution>, String> {
+    let solutions = SOLUTIONS
+        .iter()
+        .filter(|solution| selection.day.is_none_or(|day| solution.day == day))
+        .filter(|solution| selection.part.is_none_or(|part| solution.part == part))
+        .filter(|solution| solution.method == selection.method.as_deref())
+        .collect::<Vec<_>>();
+
+    if solutions.is_empty() {
+        Err("no solution registered for the selected day and part".to_string())
+    } else {
+        Ok(solutions)
+    }
+}
+
+fn execute(
+    selection: &Selection,
+    mut f: impl FnMut(&Solution, Result<Answer, String>),
+) -> Result<(), String> {
+    let mut inputs = HashMap::new();
+    for solution in select(selection)? {
+        let result = match inputs
+            .entry(solution.day)
+            .or_insert_with(|| read_input(solution, &selection.input))
+        {
+            Ok(input) => solution.run(input).map_err(|e| e.to_string()),
+            Err(e) => Err(e.clone()),
+        };
+        f(solution, result);
+    }
+    Ok(())
+}
+
+fn run(selection: &Selection) -> Result<bool, String> {
+    println!("Advent of code {}", YEAR);
+
+    let mut success = true;
+    execute(selection, |solution, result| match result {
+        Ok(answer) => println!(
+            "{solution}: {}\n\tgenerator: {:?},\n\trunner: {:?}\n",
+            answer.value, answer.parse_time, answer.solve_time
+        ),
+        Err(e) => {
+            success = false;
+            eprintln!("{solution}: {e}\n");
+        }
+    })?;
+    Ok(success)
+}
+
+fn report(selection: &Selection, format: &Format) -> Result<bool, String> {
+    let mut entries = Vec::new();
+    execute(selection, |solution, result| {
+        entries.push(Entry {
+            day: solution.day,
+            part: solution.part,
+            result,
+        })
+    })?;
+
+    match format {
+        Format::Json => print!("{}", report::to_json(&entries)),
+        Format::Csv => print!("{}", report::to_csv(&entries)),
+    }
+    Ok(entries.iter().all(|entry| entry.result.is_ok()))
+}
+
+fn verify(selection: &Selection, manifest: &str) -> Result<bool, String> {
+    let text = fs::read_to_string(manifest).map_err(|e| format!("cannot read {manifest}: {e}"))?;
+    let expected = answers::parse(&text).map_err(|e| format!("{manifest}: {e}"))?;
+    let expected = expected
+        .iter()
+        .filter(|expected| selection.day.is_none_or(|day| expected.day == day))
+        .filter(|expected| selection.part.is_none_or(|part| expected.part == part))
+        .collect::<Vec<_>>();
+
+    if expected.is_empty() {
+        return Err(format!(
+            "{manifest} has no expected answers for the selected day and part"
+        ));
+    }
+
+    let mut inputs = HashMap::new();
+    let mut failures = 0;
+    for expected in &expected {
+        let verdict = match inputs.entry(&expected.input).or_insert_with(|| {
+            fs::read_to_string(&expected.input)
+                .map_err(|e| format!("cannot read {}: {e}", expected.input))
+        }) {
+            Ok(input) => answers::check(expected, input),
+            Err(e) => Verdict::Failed(e.clone()),
+        };
+
+        let prefix = format!(
+            "Day {} - Part {} ({})",
+            expected.day, expected.part, expected.input
+        );
+        match verdict {
+            Verdict::Correct => println!("{prefix}: ok"),
+            Verdict::Mismatch { actual } => {
+                failures += 1;
+                println!(
+                    "{prefix}: MISMATCH, expected {}, got {actual}",
+                    expected.answer
+                );
+            }
+            Verdict::Failed(e) => {
+                failures += 1;
+                println!("{prefix}: {e}");
+            }
+        }
+    }
+
+    println!("\n{} correct, {failures} failed", expected.len() - failures);
+    Ok(failures == 0)
+}
+
+fn bench(selection: &Selection, options: &BenchOptions) -> Result<bool, String> {
+    let baseline = match &options.compare {
+        Some(path) => {
+            let text = fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+            bench::load_baseline(&text).map_err(|e| format!("{path}: {e}"))?
+        }
+        None => HashMap::new(),
+    };
+
+    let mut success = true;
+    let mut measurements = Vec::new();
+    let mut inputs = HashMap::new();
+    for solution in select(selection)? {
+        let samples = match inputs
+            .entry(solution.day)
+            .or_insert_with(|| read_input(solution, &selection.input))
+        {
+            Ok(input) => solution
+                .sample(input, options.iterations)
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.clone()),
+        };
+
+        let (parse, solve) = match samples {
+            Ok((parse, solve)) => (Stats::new(&parse).unwrap(), Stats::new(&solve).unwrap()),
+            Err(e) => {
+                success = false;
+                eprintln!("{solution}: {e}\n");
+                continue;
+            }
+        };
+
+        let versus = |stats: fn(&Measurement) -> &Stats, current: &Stats| {
+            baseline
+                .get(&(solution.day, solution.part))
+                .map(|old| match bench::change(stats(old), current) {
+                    Some(change) => format!(", {change:+.1}% vs baseline"),
+                    None => ", baseline median is 0".to_string(),
+                })
+                .unwrap_or_default()
+        };
+        println!(
+            "{solution} ({} iterations)\n\tparse: {parse}{}\n\tsolve: {solve}{}\n",
+            options.iterations,
+            versus(|m| &m.parse, &parse),
+            versus(|m| &m.solve, &solve),
+        );
+
+        measurements.push(Measurement {
+            day: solution.day,
+            part: solution.part,
+            parse,
+            solve,
+        });
+    }
+
+    if let Some(path) = &options.save {
+        fs::write(path, bench::save_baseline(&measurements))
+            .map_err(|e| format!("cannot write {path}: {e}"))?;
+        println!("Saved baseline to {path}");
+    }
+
+    Ok(success)
+}
+
+fn render(input: &Input, backend: Backend) -> Result<bool, String> {
+    let input = read_input(registry::find(10, 2).unwrap(), input)?;
+    print!(
+        "{}",
+        aoc_2023::render(&input, backend).map_err(|e| e.to_string())?
+    );
+    Ok(true)
+}
+
+fn loops(input: &Input) -> Result<bool, String> {
+    let input = read_input(registry::find(10, 2).unwrap(), input)?;
+    let analysis = aoc_2023::analyse_loops(&input).map_err(|e| e.to_string())?;
+    print!("{analysis}");
+    Ok(true)
+}
+
+fn hands(input: &Input, jokers: bool) -> Result<bool, String> {
+    let input = read_input(registry::find(7, 1).unwrap(), input)?;
+    print!(
+        "{}",
+        aoc_2023::audit_hands(&input, jokers).map_err(|e| e.to_string())?
+    );
+    Ok(true)
+}
+
+fn ghosts(input: &Input) -> Result<bool, String> {
+    let input = read_input(registry::find(8, 2).unwrap(), input)?;
+    let analysis = aoc_2023::analyse_ghosts(&input).map_err(|e| e.to_string())?;
+    println!("{analysis}");
+    Ok(true)
+}
+
+fn extrapolate(input: &Input, at: Option<i128>, confirmations: usize) -> Result<bool, String> {
+    let input = read_input(registry::find(9, 1).unwrap(), input)?;
+    print!(
+        "{}",
+        aoc_2023::extrapolate_sequences(&input, at, confirmations).map_err(|e| e.to_string())?
+    );
+    Ok(true)
+}
+
+fn galaxies(input: &Input, expansion: &Expansion) -> Result<bool, String> {
+    let input = read_input(registry::find(11, 1).unwrap(), input)?;
+    let total = aoc_2023::galaxy_distances(&input, expansion).map_err(|e| e.to_string())?;
+    println!("{total}");
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let result = match parse_args(env::args().skip(1)) {
+        Ok(Command::Run(selection)) => run(&selection),
+        Ok(Command::Report(selection, format)) => report(&selection, &format),
+        Ok(Command::Verify(selection, manifest)) => verify(&selection, &manifest),
+        Ok(Command::Bench(selection, options)) => bench(&selection, &options),
+        Ok(Command::Render(input, backend)) => render(&input, backend),
+        Ok(Command::Loops(input)) => loops(&input),
+        Ok(Command::Hands(input, jokers)) => hands(&input, jokers),
+        Ok(Command::Ghosts(input)) => ghosts(&input),
+        Ok(Command::Extrapolate(input, at, confirmations)) => {
+            extrapolate(&input, at, confirmations)
+        }
+        Ok(Command::Galaxies(input, expansion)) => galaxies(&input, &expansion),
+        Ok(Command::List) => {
+            for solution in SOLUTIONS {
+                match solution.method {
+                    Some(method) => {
+                        println!(
+                            "day {:>2} part {} --method {method}",
+                            solution.day, solution.part
+                        )
+                    }
+                    None => println!("day {:>2} part {}", solution.day, solution.part),
+                }
+            }
+            Ok(true)
+        }
+        Ok(Command::Help) => {
+            print!("{USAGE}");
+            Ok(true)
+        }
+        Err(e) => Err(format!("{e}\n\n{USAGE}")),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_2023::Axis;
+
+    fn args(args: &str) -> Result<Command, String> {
+        parse_args(args.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn parse_args_example() {
+        assert_eq!(
+            args(""),
+            Ok(Command::Run(Selection {
+                day: None,
+                part: None,
+                method: None,
+                input: Input::Default
+            }))
+        );
+        assert_eq!(
+            args("run --day 7 --part 2 --input -"),
+            Ok(Command::Run(Selection {
+                day: Some(7),
+                part: Some(2),
+                method: None,
+                input: Input::Stdin
+            }))
+        );
+        assert_eq!(
+            args("run -d 5 -i input/custom.txt"),
+            Ok(Command::Run(Selection {
+                day: Some(5),
+                part: None,
+                method: None,
+                input: Input::Path("input/custom.txt".into())
+            }))
+        );
+        assert_eq!(
+            args("report --format csv --day 3"),
+            Ok(Command::Report(
+                Selection {
+                    day: Some(3),
+                    part: None,
+                    method: None,
+                    input: Input::Default
+                },
+                Format::Csv
+            ))
+        );
+        assert_eq!(
+            args("verify --part 1 --manifest custom.txt"),
+            Ok(Command::Verify(
+                Selection {
+                    day: None,
+                    part: Some(1),
+                    method: None,
+                    input: Input::Default
+                },
+                "custom.txt".into()
+            ))
+        );
+        assert_eq!(
+            args("bench --day 14 --part 2 -n 50 --compare base.txt"),
+            Ok(Command::Bench(
+                Selection {
+                    day: Some(14),
+                    part: Some(2),
+                    method: None,
+                    input: Input::Default
+                },
+                BenchOptions {
+                    iterations: 50,
+                    save: None,
+                    compare: Some("base.txt".into())
+                }
+            ))
+        );
+        assert_eq!(
+            args("render"),
+            Ok(Command::Render(Input::Default, Backend::Plain))
+        );
+        assert_eq!(
+            args("render --format svg --input maze.txt"),
+            Ok(Command::Render(
+                Input::Path("maze.txt".into()),
+                Backend::Svg
+            ))
+        );
+        assert_eq!(args("loops -i -"), Ok(Command::Loops(Input::Stdin)));
+        assert_eq!(
+            args("hands --part 2"),
+            Ok(Command::Hands(Input::Default, true))
+        );
+        assert_eq!(args("ghosts"), Ok(Command::Ghosts(Input::Default)));
+        assert_eq!(
+            args("extrapolate --at -1"),
+            Ok(Command::Extrapolate(Input::Default, Some(-1), 1))
+        );
+        assert_eq!(
+            args("extrapolate --confirmations 3 -i -"),
+            Ok(Command::Extrapolate(Input::Stdin, None, 3))
+        );
+        assert_eq!(
+            args("bench -d 10 -p 2 --method shoelace"),
+            Ok(Command::Bench(
+                Selection {
+                    day: Some(10),
+                    part: Some(2),
+                    method: Some("shoelace".into()),
+                    input: Input::Default
+                },
+                BenchOptions {
+                    iterations: 20,
+                    save: None,
+                    compare: None
+                }
+            ))
+        );
+        assert_eq!(
+            args("galaxies --rows 10 --col-weights 1,2,1"),
+            Ok(Command::Galaxies(
+                Input::Default,
+                Expansion {
+                    rows: Axis::uniform(10),
+                    cols: Axis {
+                        factor: 2,
+                        weights: Some(vec![1, 2, 1])
+                    }
+                }
+            ))
+        );
+        assert_eq!(args("list"), Ok(Command::List));
+    }
+
+    #[test]
+    fn parse_args_error() {
+        assert_eq!(
+            args("run --input -"),
+            Err("`--input` requires `--day`".into())
+        );
+        assert_eq!(args("run --day"), Err("missing value for `--day`".into()));
+        assert_eq!(
+            args("run --day x"),
+            Err("invalid value `x` for `--day`: invalid digit found in string".into())
+        );
+        assert_eq!(args("fly"), Err("unknown command `fly`".into()));
+        assert_eq!(
+            args("run --format csv"),
+            Err("unexpected argument `--format`".into())
+        );
+        assert_eq!(
+            args("verify --day 1 --input -"),
+            Err("unexpected argument `--input`".into())
+        );
+        assert_eq!(
+            args("bench --iterations 0"),
+            Err("invalid value `0` for `--iterations`".into())
+        );
+        assert_eq!(
+            args("report --format xml"),
+            Err("unknown format `xml`".into())
+        );
+        assert_eq!(
+            args("render --format json"),
+            Err("unknown format `json`".into())
+        );
+        assert_eq!(
+            args("render --day 10"),
+            Err("unexpected argument `--day`".into())
+        );
+        assert_eq!(
+            args("hands --part 3"),
+            Err("invalid value `3` for `--part`".into())
+        );
+        assert_eq!(
+            args("extrapolate --at next"),
+            Err("invalid value `next` for `--at`: invalid digit found in string".into())
+        );
+        assert_eq!(
+            args("galaxies --cols 1,2"),
+            Err("invalid value `1,2` for `--cols`: invalid digit found in string".into())
+        );
+        assert_eq!(
+            args("list --day 1"),
+            Err("unexpected argument `--day`".into())
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use num_traits::{CheckedAdd, CheckedMul, Zero};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// The integer type answers are computed in: overflow-checked `u64` by default,
+/// or an arbitrary-precision integer with the `bigint` feature.
+#[cfg(not(feature = "bigint"))]
+pub type Int = u64;
+#[cfg(feature = "bigint")]
+pub type Int = num_bigint::BigUint;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Overflow;
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "answer does not fit in 64 bits, build with `--features bigint`"
+        )
+    }
+}
+
+impl Error for Overflow {}
+
+pub fn add(a: &Int, b: &Int) -> Result<Int, Overflow> {
+    a.checked_add(b).ok_or(Overflow)
+}
+
+pub fn mul(a: &Int, b: &Int) -> Result<Int, Overflow> {
+    a.checked_mul(b).ok_or(Overflow)
+}
+
+pub fn pow(base: Int, exp: usize) -> Result<Int, Overflow> {
+    num_traits::checked_pow(base, exp).ok_or(Overflow)
+}
+
+pub fn sum<I: IntoIterator<Item = Result<Int, Overflow>>>(values: I) -> Result<Int, Overflow> {
+    values
+        .into_iter()
+        .try_fold(Int::zero(), |total, value| add(&total, &value?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::One;
+
+    #[test]
+    fn checked() {
+        let big = Int::from(u64::MAX);
+        let one = Int::from(1u64);
+
+        assert_eq!(
+            sum([Ok(Int::from(2u64)), Ok(Int::from(3u64))]),
+            Ok(Int::from(5u64))
+        );
+        assert_eq!(pow(Int::from(2u64), 10), Ok(Int::from(1024u64)));
+        assert_eq!(sum([Ok(Int::one()), Err(Overflow)]), Err(Overflow));
+
+        #[cfg(not(feature = "bigint"))]
+        {
+            assert_eq!(add(&big, &one), Err(Overflow));
+            assert_eq!(mul(&big, &big), Err(Overflow));
+            assert_eq!(pow(Int::from(2u64), 64), Err(Overflow));
+        }
+        #[cfg(feature = "bigint")]
+        {
+            assert_eq!(add(&big, &one).unwrap().to_string(), "18446744073709551616");
+            assert_eq!(
+                pow(Int::from(2u64), 100).unwrap().to_string(),
+                "1267650600228229401496703205376"
+            );
+            assert_eq!(mul(&big, &big), pow(big.clone(), 2));
+        }
+    }
+}
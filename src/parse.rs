@@ -0,0 +1,148 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: {} (at {:?})",
+            self.day, self.line, self.column, self.message, self.text
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// The raw input of a day, used to turn any slice of it back into a line and column.
+#[derive(Debug, Clone, Copy)]
+pub struct Source<'a> {
+    day: u8,
+    input: &'a str,
+}
+
+impl<'a> Source<'a> {
+    pub fn new(day: u8, input: &'a str) -> Self {
+        Self { day, input }
+    }
+
+    pub fn error(&self, at: &str, message: impl Into<String>) -> ParseError {
+        let offset = (at.as_ptr() as usize)
+            .checked_sub(self.input.as_ptr() as usize)
+            .filter(|&offset| offset <= self.input.len() && self.input.is_char_boundary(offset))
+            .unwrap_or(self.input.len());
+
+        let before = &self.input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let text = if at.is_empty() {
+            self.input[line_start..].lines().next().unwrap_or_default()
+        } else {
+            at
+        };
+
+        ParseError {
+            day: self.day,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn parse<T>(&self, at: &'a str) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        at.parse().map_err(|e| self.error(at, format!("{e}")))
+    }
+
+    pub fn split_once(
+        &self,
+        at: &'a str,
+        delimiter: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        at.split_once(delimiter)
+            .ok_or_else(|| self.error(at, format!("expected {delimiter:?}")))
+    }
+
+    pub fn strip_prefix(&self, at: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+        at.strip_prefix(prefix)
+            .ok_or_else(|| self.error(at, format!("expected {prefix:?}")))
+    }
+
+    pub fn strip_suffix(&self, at: &'a str, suffix: &str) -> Result<&'a str, ParseError> {
+        at.strip_suffix(suffix)
+            .ok_or_else(|| self.error(at, format!("expected {suffix:?}")))
+    }
+
+    /// Splits a line into its characters, each paired with the slice it occupies.
+    pub fn chars(line: &'a str) -> impl Iterator<Item = (char, &'a str)> {
+        line.char_indices()
+            .map(move |(i, c)| (c, &line[i..i + c.len_utf8()]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn error_position() {
+        let input = indoc! {"
+            Game 1: 3 blue
+            Game 2: x red
+        "};
+        let source = Source::new(2, input);
+        let line = input.lines().nth(1).unwrap();
+
+        assert_eq!(
+            source.parse::<u32>(&line[8..9]),
+            Err(ParseError {
+                day: 2,
+                line: 2,
+                column: 9,
+                text: "x".into(),
+                message: "invalid digit found in string".into(),
+            })
+        );
+        assert_eq!(
+            source.split_once(line, ";"),
+            Err(ParseError {
+                day: 2,
+                line: 2,
+                column: 1,
+                text: "Game 2: x red".into(),
+                message: "expected \";\"".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn error_empty_text() {
+        let input = "1 2\n3 4";
+        let source = Source::new(9, input);
+
+        let error = source.error(&input[input.len()..], "missing value");
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.text, "3 4");
+
+        let error = source.error(&input[4..4], "missing value");
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.text, "3 4");
+        assert_eq!(
+            error.to_string(),
+            "day 9, line 2, column 1: missing value (at \"3 4\")"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::*;
+use aoc_runner::{ArcStr, Runner};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::panic::{self, UnwindSafe};
+use std::time::{Duration, Instant};
+
+type Factory = fn(ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>>;
+
+#[derive(Clone, Copy)]
+pub struct Solution {
+    pub day: u8,
+    pub part: u8,
+    /// The name of an alternative solver, `None` for the default one.
+    pub method: Option<&'static str>,
+    factory: Factory,
+}
+
+macro_rules! solutions {
+    ($(($day:literal, $part:literal, $($method:literal,)? $factory:ident)),* $(,)?) => {
+        pub static SOLUTIONS: &[Solution] = &[$(Solution {
+            day: $day,
+            part: $part,
+            method: solutions!(@method $($method)?),
+            factory: crate::Factory::$factory,
+        }),*];
+    };
+    (@method) => { None };
+    (@method $method:literal) => { Some($method) };
+}
+
+solutions![
+    (1, 1, day1_part1),
+    (1, 2, day1_part2),
+    (2, 1, day2_part1),
+    (2, 2, day2_part2),
+    (3, 1, day3_part1),
+    (3, 2, day3_part2),
+    (4, 1, day4_part1),
+    (4, 2, day4_part2),
+    (5, 1, day5_part1),
+    (5, 2, day5_part2),
+    (6, 1, day6_part1),
+    (6, 2, day6_part2),
+    (7, 1, day7_part1),
+    (7, 2, day7_part2),
+    (8, 1, day8_part1),
+    (8, 2, day8_part2),
+    (9, 1, day9_part1),
+    (9, 2, day9_part2),
+    (10, 1, day10_part1),
+    (10, 2, day10_part2),
+    (10, 2, "scanline", day10_part2_scanline),
+    (10, 2, "shoelace", day10_part2_shoelace),
+    (11, 1, day11_part1),
+    (11, 2, day11_part2),
+    (12, 1, day12_part1),
+    (12, 2, day12_part2),
+    (13, 1, day13_part1),
+    (13, 2, day13_part2),
+    (14, 1, day14_part1),
+    (14, 2, day14_part2),
+    (15, 1, day15_part1),
+    (15, 2, day15_part2),
+];
+
+/// Finds the default solver for a day and part.
+pub fn find(day: u8, part: u8) -> Option<&'static Solution> {
+    SOLUTIONS
+        .iter()
+        .find(|solution| solution.day == day && solution.part == part && solution.method.is_none())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub value: String,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+#[derive(Debug)]
+pub enum RunError {
+    Generating(Box<dyn Error>),
+    Running(Box<dyn Error>),
+    Panicked(String),
+}
+
+impl Display for RunError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Generating(e) => write!(f, "FAILED while generating: {e}"),
+            RunError::Running(e) => write!(f, "FAILED while running: {e}"),
+            RunError::Panicked(message) => write!(f, "PANICKED: {message}"),
+        }
+    }
+}
+
+impl Error for RunError {}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Day {} - Part {}", self.day, self.part)?;
+        match self.method {
+            Some(method) => write!(f, " ({method})"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Solution {
+    pub fn default_input(&self) -> String {
+        format!("input/{}/day{}.txt", YEAR, self.day)
+    }
+
+    pub fn run(&self, input: &str) -> Result<Answer, RunError> {
+        catch_panic(|| self.run_unchecked(input))
+    }
+
+    fn run_unchecked(&self, input: &str) -> Result<Answer, RunError> {
+        let start_time = Instant::now();
+        let runner = (self.factory)(ArcStr::from(input)).map_err(RunError::Generating)?;
+        let inter_time = Instant::now();
+        let value = runner.try_run().map_err(RunError::Running)?;
+        let final_time = Instant::now();
+
+        Ok(Answer {
+            value: value.to_string(),
+            parse_time: inter_time - start_time,
+            solve_time: final_time - inter_time,
+        })
+    }
+
+    /// Times the generator and the solver `iterations` times each, returning both sets of samples.
+    ///
+    /// Solvers must not keep state between calls, or every sample after the first measures a cache hit.
+    pub fn sample(
+        &self,
+        input: &str,
+        iterations: usize,
+    ) -> Result<(Vec<Duration>, Vec<Duration>), RunError> {
+        catch_panic(|| {
+            let input = ArcStr::from(input);
+            let mut parse_times = Vec::with_capacity(iterations);
+            let mut solve_times = Vec::with_capacity(iterations);
+            for _ in 0..iterations {
+                let start_time = Instant::now();
+                let runner = (self.factory)(input.clone()).map_err(RunError::Generating)?;
+                let inter_time = Instant::now();
+                runner.try_run().map_err(RunError::Running)?;
+                let final_time = Instant::now();
+
+                parse_times.push(inter_time - start_time);
+                solve_times.push(final_time - inter_time);
+            }
+            Ok((parse_times, solve_times))
+        })
+    }
+}
+
+fn catch_panic<T>(f: impl FnOnce() -> Result<T, RunError> + UnwindSafe) -> Result<T, RunError> {
+    panic::catch_unwind(f).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Err(RunError::Panicked(message))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn solutions_are_unique_and_sorted() {
+        assert!(SOLUTIONS
+            .iter()
+            .tuple_windows()
+            .all(|(a, b)| (a.day, a.part, a.method) < (b.day, b.part, b.method)));
+    }
+
+    #[test]
+    fn run_solution() {
+        let solution = find(15, 1).unwrap();
+        let answer = solution.run("rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7\n");
+        assert_eq!(answer.unwrap().value, "1320");
+        assert_eq!(solution.default_input(), "input/2023/day15.txt");
+
+        let error = find(7, 1).unwrap().run("32T3K x").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "FAILED while generating: day 7, line 1, column 7: invalid digit found in string (at \"x\")"
+        );
+        assert!(find(16, 1).is_none());
+        assert_eq!(find(10, 2).unwrap().to_string(), "Day 10 - Part 2");
+        assert_eq!(
+            SOLUTIONS
+                .iter()
+                .filter(|solution| solution.method.is_some())
+                .map(|solution| solution.to_string())
+                .collect::<Vec<_>>(),
+            ["Day 10 - Part 2 (scanline)", "Day 10 - Part 2 (shoelace)"]
+        );
+
+        let (parse_times, solve_times) = solution.sample("HASH", 3).unwrap();
+        assert_eq!((parse_times.len(), solve_times.len()), (3, 3));
+
+        let springs = find(12, 2).unwrap();
+        springs.sample("???.### 1,1,3\n", 3).unwrap();
+        let answer = springs.run("?###???????? 3,2,1\n").unwrap();
+        assert_eq!(answer.value, "506250");
+
+        let error = find(6, 1).unwrap().run("Time: 7").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "PANICKED: called `Option::unwrap()` on a `None` value"
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use crate::registry::Answer;
+use crate::YEAR;
+use itertools::Itertools;
+
+#[derive(Debug)]
+pub struct Entry {
+    pub day: u8,
+    pub part: u8,
+    pub result: Result<Answer, String>,
+}
+
+fn json_string(value: &str) -> String {
+    let mut result = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => result += "\\\"",
+            '\\' => result += "\\\\",
+            '\n' => result += "\\n",
+            '\r' => result += "\\r",
+            '\t' => result += "\\t",
+            c if c.is_control() => result += &format!("\\u{:04x}", c as u32),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn to_json(entries: &[Entry]) -> String {
+    let results = entries
+        .iter()
+        .map(|entry| {
+            let (answer, parse_time, solve_time, error) = match &entry.result {
+                Ok(answer) => (
+                    json_string(&answer.value),
+                    answer.parse_time.as_nanos().to_string(),
+                    answer.solve_time.as_nanos().to_string(),
+                    "null".to_string(),
+                ),
+                Err(e) => (
+                    "null".to_string(),
+                    "null".to_string(),
+                    "null".to_string(),
+                    json_string(e),
+                ),
+            };
+            format!(
+                "    {{\"day\": {}, \"part\": {}, \"answer\": {answer}, \"parse_time_ns\": {parse_time}, \"solve_time_ns\": {solve_time}, \"error\": {error}}}",
+                entry.day, entry.part
+            )
+        })
+        .join(",\n");
+
+    if results.is_empty() {
+        format!("{{\n  \"year\": {YEAR},\n  \"results\": []\n}}\n")
+    } else {
+        format!("{{\n  \"year\": {YEAR},\n  \"results\": [\n{results}\n  ]\n}}\n")
+    }
+}
+
+pub fn to_csv(entries: &[Entry]) -> String {
+    let mut result = String::from("day,part,answer,parse_time_ns,solve_time_ns,error\n");
+    for entry in entries {
+        let line = match &entry.result {
+            Ok(answer) => format!(
+                "{},{},{},{},{},",
+                entry.day,
+                entry.part,
+                csv_field(&answer.value),
+                answer.parse_time.as_nanos(),
+                answer.solve_time.as_nanos()
+            ),
+            Err(e) => format!("{},{},,,,{}", entry.day, entry.part, csv_field(e)),
+        };
+        result += &line;
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::time::Duration;
+
+    fn entries() -> Vec<Entry> {
+        vec![
+            Entry {
+                day: 1,
+                part: 1,
+                result: Ok(Answer {
+                    value: "142".into(),
+                    parse_time: Duration::from_nanos(12),
+                    solve_time: Duration::from_micros(3),
+                }),
+            },
+            Entry {
+                day: 7,
+                part: 2,
+                result: Err("FAILED while generating: unknown card 'X' (at \"X\")".into()),
+            },
+        ]
+    }
+
+    #[test]
+    fn to_json_example() {
+        let expected = indoc! {r#"
+            {
+              "year": 2023,
+              "results": [
+                {"day": 1, "part": 1, "answer": "142", "parse_time_ns": 12, "solve_time_ns": 3000, "error": null},
+                {"day": 7, "part": 2, "answer": null, "parse_time_ns": null, "solve_time_ns": null, "error": "FAILED while generating: unknown card 'X' (at \"X\")"}
+              ]
+            }
+        "#};
+
+        assert_eq!(to_json(&entries()), expected);
+        assert_eq!(to_json(&[]), "{\n  \"year\": 2023,\n  \"results\": []\n}\n");
+    }
+
+    #[test]
+    fn to_csv_example() {
+        let expected = indoc! {r#"
+            day,part,answer,parse_time_ns,solve_time_ns,error
+            1,1,142,12,3000,
+            7,2,,,,"FAILED while generating: unknown card 'X' (at ""X"")"
+        "#};
+
+        assert_eq!(to_csv(&entries()), expected);
+    }
+
+    #[test]
+    fn json_string_escapes() {
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), r#""a\"b\\c\nd\u0001""#);
+    }
+}